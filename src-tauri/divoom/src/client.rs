@@ -0,0 +1,665 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use base64::{engine::general_purpose, Engine as _};
+use image::codecs::jpeg::JpegEncoder;
+use image::{DynamicImage, ImageEncoder};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+use crate::error::{Error, Result};
+use crate::models::{
+    BuzzerTiming, Channel, ChannelSelection, CloudChannel, DeviceSettings, DisplayItem,
+    StopwatchAction, TextConfig,
+};
+use crate::protocol::{
+    AllConf, CommandResponse, DeviceTime, HttpGif, HttpGifId, HttpItem, HttpText, PcParaScreen,
+    Request, WeatherInfo,
+};
+use crate::text::{Color, Font, TextAlignment};
+use crate::{LCD_COUNT, LCD_SIZE, MAX_FRAMES, MAX_SCORE, MAX_TIMER_MINUTES};
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_millis(500);
+// Image uploads carry a base64 JPEG and take noticeably longer to be acknowledged
+const IMAGE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Gets notified about every command sent by a [`DivoomClient`], e.g. to collect
+/// latency statistics.
+pub trait CommandObserver: Send + Sync {
+    fn started(&self, ip: &str);
+    fn finished(&self, ip: &str, latency: Duration, result: std::result::Result<(), &Error>);
+}
+
+/// Sends commands to one device over its local HTTP API (`POST http://<ip>/post`).
+#[derive(Clone)]
+pub struct DivoomClient {
+    ip: String,
+    http: reqwest::Client,
+    timeout: Duration,
+    observer: Option<Arc<dyn CommandObserver>>,
+}
+
+impl DivoomClient {
+    pub fn new(ip: impl Into<String>) -> Self {
+        Self {
+            ip: ip.into(),
+            http: reqwest::Client::new(),
+            timeout: DEFAULT_TIMEOUT,
+            observer: None,
+        }
+    }
+
+    /// Timeout of regular commands; image uploads wait at least one second.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn with_observer(mut self, observer: Arc<dyn CommandObserver>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    pub fn ip(&self) -> &str {
+        &self.ip
+    }
+
+    /// Sends a command and parses the device's answer into `T`.
+    pub async fn request<T: DeserializeOwned>(&self, request: &Request) -> Result<T> {
+        let answer = self.execute(request, self.timeout).await?;
+        Ok(serde_json::from_value(answer)?)
+    }
+
+    /// Sends a command whose answer carries nothing but the error code.
+    pub async fn send(&self, request: &Request) -> Result<()> {
+        self.execute(request, self.timeout).await.map(|_| ())
+    }
+
+    async fn execute(&self, request: &Request, timeout: Duration) -> Result<serde_json::Value> {
+        if let Some(observer) = &self.observer {
+            observer.started(&self.ip);
+        }
+
+        let started_at = Instant::now();
+        let result = self.post(request, timeout).await;
+
+        if let Some(observer) = &self.observer {
+            observer.finished(&self.ip, started_at.elapsed(), result.as_ref().map(|_| ()));
+        }
+
+        result
+    }
+
+    async fn post(&self, request: &Request, timeout: Duration) -> Result<serde_json::Value> {
+        let response = self
+            .http
+            .post(format!("http://{}/post", self.ip))
+            .timeout(timeout)
+            .json(request)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(Error::Status(response.status()));
+        }
+
+        let answer: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| Error::InvalidResponse(e.to_string()))?;
+
+        let envelope = CommandResponse::deserialize(&answer).unwrap_or_default();
+        if envelope.error_code != 0 {
+            return Err(Error::Device(envelope.error_code));
+        }
+
+        Ok(answer)
+    }
+
+    pub async fn get_all_conf(&self) -> Result<DeviceSettings> {
+        let conf: AllConf = self.request(&Request::GetAllConf).await?;
+        Ok(conf.into())
+    }
+
+    pub async fn get_device_time(&self) -> Result<DeviceTime> {
+        self.request(&Request::GetDeviceTime).await
+    }
+
+    /// Full device state: `Channel/GetAllConf` plus the device clock when available.
+    pub async fn get_device_state(&self) -> Result<DeviceSettings> {
+        let mut settings = self.get_all_conf().await?;
+
+        // Older firmwares do not know the command; the configuration is still useful
+        if let Ok(time) = self.get_device_time().await {
+            settings.device_utc_time = time.utc_time;
+            settings.device_local_time = time.local_time;
+        }
+
+        Ok(settings)
+    }
+
+    /// Sets the location the device fetches its weather for.
+    pub async fn set_location(&self, longitude: f64, latitude: f64) -> Result<()> {
+        if !(-180.0..=180.0).contains(&longitude) || !(-90.0..=90.0).contains(&latitude) {
+            return Err(Error::InvalidArgument(format!(
+                "invalid coordinates {}, {}",
+                longitude, latitude
+            )));
+        }
+
+        self.send(&Request::SetLocation {
+            longitude: longitude.to_string(),
+            latitude: latitude.to_string(),
+        })
+        .await
+    }
+
+    pub async fn get_weather(&self) -> Result<WeatherInfo> {
+        self.request(&Request::GetWeatherInfo).await
+    }
+
+    /// `time_zone` is an offset from UTC such as `GMT+3` or `GMT-5`.
+    pub async fn set_time_zone(&self, time_zone: &str) -> Result<()> {
+        self.send(&Request::SetTimeZone {
+            time_zone_value: time_zone.to_string(),
+        })
+        .await
+    }
+
+    /// Sets the device clock to a Unix time in seconds.
+    pub async fn set_utc(&self, utc: i64) -> Result<()> {
+        self.send(&Request::SetUtc { utc }).await
+    }
+
+    /// `brightness` is 0-100.
+    pub async fn set_brightness(&self, brightness: u8) -> Result<()> {
+        if brightness > 100 {
+            return Err(Error::InvalidArgument(format!(
+                "brightness must be 0-100, got {}",
+                brightness
+            )));
+        }
+
+        self.send(&Request::SetBrightness { brightness }).await
+    }
+
+    pub async fn set_screen_on(&self, on: bool) -> Result<()> {
+        self.send(&Request::OnOffScreen {
+            on_off: u8::from(on),
+        })
+        .await
+    }
+
+    pub async fn set_temperature_mode(&self, fahrenheit: bool) -> Result<()> {
+        self.send(&Request::SetDisTempMode {
+            mode: u8::from(fahrenheit),
+        })
+        .await
+    }
+
+    pub async fn set_mirror_mode(&self, enabled: bool) -> Result<()> {
+        self.send(&Request::SetMirrorMode {
+            mode: u8::from(enabled),
+        })
+        .await
+    }
+
+    pub async fn set_24_hour_mode(&self, enabled: bool) -> Result<()> {
+        self.send(&Request::SetTime24Flag {
+            mode: u8::from(enabled),
+        })
+        .await
+    }
+
+    /// Plays the buzzer, e.g. with [`crate::BuzzerPattern::timing`].
+    pub async fn play_buzzer(&self, timing: BuzzerTiming) -> Result<()> {
+        if timing.active_ms == 0 || timing.total_ms == 0 {
+            return Err(Error::InvalidArgument(
+                "buzzer active and total time must be greater than zero".to_string(),
+            ));
+        }
+
+        self.send(&Request::PlayBuzzer {
+            active_time_in_cycle: timing.active_ms,
+            off_time_in_cycle: timing.off_ms,
+            play_total_time: timing.total_ms,
+        })
+        .await
+    }
+
+    pub async fn reboot(&self) -> Result<()> {
+        self.send(&Request::SysReboot).await
+    }
+
+    /// Starts a countdown of `minutes:seconds`, at most 99:59, or stops it when
+    /// `running` is false.
+    pub async fn set_timer(&self, minutes: u32, seconds: u32, running: bool) -> Result<()> {
+        if minutes > MAX_TIMER_MINUTES || seconds > 59 {
+            return Err(Error::InvalidArgument(format!(
+                "the timer runs 0:00-{}:59, got {}:{:02}",
+                MAX_TIMER_MINUTES, minutes, seconds
+            )));
+        }
+
+        self.send(&Request::SetTimer {
+            minute: minutes,
+            second: seconds,
+            status: u8::from(running),
+        })
+        .await
+    }
+
+    pub async fn set_stopwatch(&self, action: StopwatchAction) -> Result<()> {
+        self.send(&Request::SetStopWatch {
+            status: action.status(),
+        })
+        .await
+    }
+
+    /// Shows the scoreboard tool; scores are 0-[`crate::MAX_SCORE`].
+    pub async fn set_scoreboard(&self, red: u16, blue: u16) -> Result<()> {
+        if red > MAX_SCORE || blue > MAX_SCORE {
+            return Err(Error::InvalidArgument(format!(
+                "scores must be 0-{}, got {} and {}",
+                MAX_SCORE, red, blue
+            )));
+        }
+
+        self.send(&Request::SetScoreBoard {
+            blue_score: blue,
+            red_score: red,
+        })
+        .await
+    }
+
+    pub async fn set_noise_meter(&self, running: bool) -> Result<()> {
+        self.send(&Request::SetNoiseStatus {
+            noise_status: u8::from(running),
+        })
+        .await
+    }
+
+    /// Shows or replaces the text element `text.id` on one LCD.
+    ///
+    /// The position is only checked against the Times Gate's LCD size; use
+    /// [`TextConfig::validate`] with the device's resolution for smaller panels.
+    pub async fn send_text(&self, lcd_index: u32, text: &TextConfig) -> Result<()> {
+        text.validate(LCD_SIZE)?;
+
+        self.send(&Request::SendHttpText(HttpText {
+            lcd_index,
+            text_id: text.id,
+            x: text.x,
+            y: text.y,
+            dir: text.direction.unwrap_or_default().code(),
+            font: text.font.unwrap_or(Font::Impact).code(),
+            text_width: text.text_width.unwrap_or(TextConfig::DEFAULT_WIDTH),
+            speed: text.speed.unwrap_or_default().step_ms(),
+            text_string: text.content.clone(),
+            color: text.color.unwrap_or(Color::WHITE).to_string(),
+            align: text.alignment.unwrap_or(TextAlignment::Scroll).code(),
+        }))
+        .await
+    }
+
+    /// Shows `items` on one LCD in a single request, so the layout changes at once
+    /// instead of element by element.
+    pub async fn send_items(&self, lcd_index: u32, items: &[DisplayItem]) -> Result<()> {
+        if items.is_empty() {
+            return Err(Error::InvalidArgument(
+                "an item list needs at least one item; use clear_text to remove all".to_string(),
+            ));
+        }
+        for (i, item) in items.iter().enumerate() {
+            item.text.validate(LCD_SIZE)?;
+            if items[..i].iter().any(|other| other.text.id == item.text.id) {
+                return Err(Error::InvalidArgument(format!(
+                    "text id {} is used more than once",
+                    item.text.id
+                )));
+            }
+        }
+
+        let item_list = items
+            .iter()
+            .map(|item| {
+                let text = &item.text;
+                HttpItem {
+                    text_id: text.id,
+                    item_type: item.kind.code(),
+                    x: text.x,
+                    y: text.y,
+                    dir: text.direction.unwrap_or_default().code(),
+                    font: text.font.unwrap_or(Font::Impact).code(),
+                    text_width: text.text_width.unwrap_or(TextConfig::DEFAULT_WIDTH),
+                    text_height: 16,
+                    speed: text.speed.unwrap_or_default().step_ms(),
+                    text_string: text.content.clone(),
+                    color: text.color.unwrap_or(Color::WHITE).to_string(),
+                    align: text.alignment.unwrap_or(TextAlignment::Scroll).code(),
+                }
+            })
+            .collect();
+
+        self.send(&Request::SendHttpItemList {
+            lcd_index,
+            item_list,
+        })
+        .await
+    }
+
+    /// Removes every text element from one LCD.
+    pub async fn clear_text(&self, lcd_index: u32) -> Result<()> {
+        self.send(&Request::ClearHttpText { lcd_index }).await
+    }
+
+    /// Last PicID the device accepted; uploads need a greater one.
+    pub async fn get_pic_id(&self) -> Result<u32> {
+        let answer: HttpGifId = self.request(&Request::GetHttpGifId).await?;
+        Ok(answer.pic_id)
+    }
+
+    /// Resets the device's PicID sequence; the next upload may use PicID 1 again.
+    pub async fn reset_pic_id(&self) -> Result<()> {
+        self.send(&Request::ResetHttpGifId).await
+    }
+
+    /// Scales `img` to the LCD size and shows it as a single-frame animation on
+    /// every LCD in `lcds`.
+    ///
+    /// The device ignores uploads whose `pic_id` is not greater than the last one.
+    pub async fn send_image(&self, lcds: &[u32], pic_id: u32, img: &DynamicImage) -> Result<()> {
+        self.send_animation(lcds, pic_id, std::slice::from_ref(img), 1000)
+            .await
+    }
+
+    /// Uploads `frames` one request at a time; the device starts looping them on
+    /// every LCD in `lcds` once the last frame arrived, showing each for `speed_ms`.
+    pub async fn send_animation(
+        &self,
+        lcds: &[u32],
+        pic_id: u32,
+        frames: &[DynamicImage],
+        speed_ms: u32,
+    ) -> Result<()> {
+        if frames.is_empty() || frames.len() > MAX_FRAMES {
+            return Err(Error::InvalidArgument(format!(
+                "an animation needs 1-{} frames, got {}",
+                MAX_FRAMES,
+                frames.len()
+            )));
+        }
+
+        let lcd_array = lcd_array(lcds)?;
+
+        for (offset, frame) in frames.iter().enumerate() {
+            let image_data = encode_jpeg(frame, LCD_SIZE, LCD_SIZE)?;
+            let request = Request::SendHttpGif(HttpGif {
+                lcd_array,
+                pic_num: frames.len() as u32,
+                pic_width: LCD_SIZE,
+                pic_offset: offset as u32,
+                pic_id,
+                pic_speed: speed_ms,
+                pic_data: general_purpose::STANDARD.encode(&image_data),
+            });
+
+            self.execute(&request, self.timeout.max(IMAGE_TIMEOUT))
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Shows `tiles[i]` on LCD `i`, each tile being the frames of one animation.
+    ///
+    /// Tile `i` is uploaded with PicID `first_pic_id + i`, all frames of a tile before
+    /// the next one, so PicIDs never go down. Up to one request per LCD goes out in a
+    /// single `Draw/CommandList`, which shows a one-frame panorama on all LCDs at once;
+    /// firmware that rejects the batch gets the requests one at a time instead.
+    pub async fn send_panorama(
+        &self,
+        first_pic_id: u32,
+        tiles: &[Vec<DynamicImage>],
+        speed_ms: u32,
+    ) -> Result<()> {
+        let requests = panorama_requests(first_pic_id, tiles, speed_ms)?;
+        let timeout = self.timeout.max(IMAGE_TIMEOUT) * LCD_COUNT as u32;
+        let mut batch = true;
+
+        for (index, chunk) in requests.chunks(LCD_COUNT).enumerate() {
+            if batch {
+                let list = Request::CommandList {
+                    command_list: chunk.to_vec(),
+                };
+                match self.execute(&list, timeout).await {
+                    Ok(_) => continue,
+                    // Only the first batch decides; later failures are real errors
+                    Err(Error::Device(_) | Error::Status(_)) if index == 0 => batch = false,
+                    Err(e) => return Err(e),
+                }
+            }
+
+            for request in chunk {
+                self.execute(request, self.timeout.max(IMAGE_TIMEOUT))
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn set_channel(&self, channel: Channel) -> Result<()> {
+        self.send(&Request::SetIndex {
+            select_index: channel.index(),
+        })
+        .await
+    }
+
+    /// Current channel and clock face, read from `Channel/GetAllConf`.
+    pub async fn get_channel_selection(&self) -> Result<ChannelSelection> {
+        let conf: AllConf = self.request(&Request::GetAllConf).await?;
+        Ok(ChannelSelection {
+            channel: conf.select_index.and_then(Channel::from_index),
+            clock_id: conf.cur_clock_id,
+            custom_page_index: conf.custom_page_index,
+        })
+    }
+
+    /// Picks one of the three custom pages (0-2) and switches to the custom channel.
+    pub async fn set_custom_page(&self, index: u8) -> Result<()> {
+        if index > 2 {
+            return Err(Error::InvalidArgument(format!(
+                "custom page must be 0-2, got {}",
+                index
+            )));
+        }
+
+        self.send(&Request::SetCustomPageIndex {
+            custom_page_index: index,
+        })
+        .await
+    }
+
+    /// Switches to the visualizer at `position` in the app's visualizer list.
+    pub async fn set_visualizer(&self, position: u8) -> Result<()> {
+        self.send(&Request::SetEqPosition {
+            eq_position: position,
+        })
+        .await
+    }
+
+    pub async fn set_cloud_channel(&self, channel: CloudChannel) -> Result<()> {
+        self.send(&Request::CloudIndex {
+            index: channel.index(),
+        })
+        .await
+    }
+
+    /// Switches one LCD to a clock face, e.g. [`crate::PC_MONITOR_CLOCK_ID`].
+    pub async fn select_clock(
+        &self,
+        device_id: u64,
+        lcd_independence: u64,
+        lcd_index: u32,
+        clock_id: u64,
+    ) -> Result<()> {
+        self.send(&Request::SetClockSelectId {
+            lcd_independence,
+            device_id,
+            lcd_index,
+            clock_id,
+        })
+        .await
+    }
+
+    /// Updates the six values shown by the PC Monitor clock face.
+    pub async fn update_pc_monitor(&self, lcd_index: u32, disp_data: &[String]) -> Result<()> {
+        self.send(&Request::UpdatePcParaInfo {
+            screen_list: vec![PcParaScreen {
+                lcd_id: lcd_index,
+                disp_data: disp_data.to_vec(),
+            }],
+        })
+        .await
+    }
+}
+
+/// `Draw/SendHttpGif` requests of a panorama in upload order: tile by tile, each
+/// tile's frames in order.
+fn panorama_requests(
+    first_pic_id: u32,
+    tiles: &[Vec<DynamicImage>],
+    speed_ms: u32,
+) -> Result<Vec<Request>> {
+    if tiles.is_empty() || tiles.len() > LCD_COUNT {
+        return Err(Error::InvalidArgument(format!(
+            "a panorama needs 1-{} tiles, got {}",
+            LCD_COUNT,
+            tiles.len()
+        )));
+    }
+    let frame_count = tiles[0].len();
+    if frame_count == 0 || frame_count > MAX_FRAMES {
+        return Err(Error::InvalidArgument(format!(
+            "an animation needs 1-{} frames, got {}",
+            MAX_FRAMES, frame_count
+        )));
+    }
+    if tiles.iter().any(|tile| tile.len() != frame_count) {
+        return Err(Error::InvalidArgument(
+            "all panorama tiles need the same number of frames".to_string(),
+        ));
+    }
+
+    let mut requests = Vec::with_capacity(tiles.len() * frame_count);
+    for (lcd_index, tile) in tiles.iter().enumerate() {
+        let mut lcd_array = [0u8; LCD_COUNT];
+        lcd_array[lcd_index] = 1;
+
+        for (offset, frame) in tile.iter().enumerate() {
+            let image_data = encode_jpeg(frame, LCD_SIZE, LCD_SIZE)?;
+            requests.push(Request::SendHttpGif(HttpGif {
+                lcd_array,
+                pic_num: frame_count as u32,
+                pic_width: LCD_SIZE,
+                pic_offset: offset as u32,
+                pic_id: first_pic_id + lcd_index as u32,
+                pic_speed: speed_ms,
+                pic_data: general_purpose::STANDARD.encode(&image_data),
+            }));
+        }
+    }
+    Ok(requests)
+}
+
+/// `LCDArray` flags for `lcds`, rejecting an empty set and indices past the last LCD.
+fn lcd_array(lcds: &[u32]) -> Result<[u8; LCD_COUNT]> {
+    if lcds.is_empty() {
+        return Err(Error::InvalidArgument("no target LCD given".to_string()));
+    }
+
+    let mut lcd_array = [0u8; LCD_COUNT];
+    for &lcd_index in lcds {
+        let flag = lcd_array.get_mut(lcd_index as usize).ok_or_else(|| {
+            Error::InvalidArgument(format!(
+                "LCD index {} is out of range 0-{}",
+                lcd_index,
+                LCD_COUNT - 1
+            ))
+        })?;
+        *flag = 1;
+    }
+    Ok(lcd_array)
+}
+
+fn encode_jpeg(img: &DynamicImage, width: u32, height: u32) -> Result<Vec<u8>> {
+    let resized = img.resize_exact(width, height, image::imageops::FilterType::Lanczos3);
+    let rgba = resized.to_rgba8();
+    let mut buffer = Vec::new();
+    JpegEncoder::new(&mut buffer).write_image(
+        rgba.as_raw(),
+        rgba.width(),
+        rgba.height(),
+        image::ColorType::Rgba8,
+    )?;
+
+    Ok(buffer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tiles(count: usize, frames: usize) -> Vec<Vec<DynamicImage>> {
+        vec![vec![DynamicImage::new_rgb8(8, 8); frames]; count]
+    }
+
+    fn uploads(requests: &[Request]) -> Vec<(u32, u32, usize)> {
+        requests
+            .iter()
+            .map(|request| match request {
+                Request::SendHttpGif(gif) => (
+                    gif.pic_id,
+                    gif.pic_offset,
+                    gif.lcd_array.iter().position(|&flag| flag == 1).unwrap(),
+                ),
+                other => panic!("unexpected request {:?}", other),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn panorama_pic_ids_never_go_down() {
+        let requests = panorama_requests(10, &tiles(LCD_COUNT, 3), 100).unwrap();
+
+        let expected: Vec<(u32, u32, usize)> = (0..LCD_COUNT)
+            .flat_map(|lcd| (0..3).map(move |offset| (10 + lcd as u32, offset, lcd)))
+            .collect();
+        assert_eq!(uploads(&requests), expected);
+
+        let pic_ids: Vec<u32> = expected.iter().map(|(pic_id, _, _)| *pic_id).collect();
+        assert!(pic_ids.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
+    #[test]
+    fn single_frame_panorama_fits_one_batch() {
+        let requests = panorama_requests(1, &tiles(LCD_COUNT, 1), 1000).unwrap();
+
+        assert_eq!(requests.chunks(LCD_COUNT).count(), 1);
+        assert_eq!(
+            uploads(&requests),
+            vec![(1, 0, 0), (2, 0, 1), (3, 0, 2), (4, 0, 3), (5, 0, 4)]
+        );
+    }
+
+    #[test]
+    fn panorama_rejects_uneven_tiles() {
+        let mut uneven = tiles(2, 2);
+        uneven[1].pop();
+
+        assert!(panorama_requests(1, &uneven, 100).is_err());
+        assert!(panorama_requests(1, &tiles(LCD_COUNT + 1, 1), 100).is_err());
+        assert!(panorama_requests(1, &tiles(2, 0), 100).is_err());
+    }
+}
@@ -0,0 +1,136 @@
+//! Requests to the Divoom cloud service, used for discovery, LCD layout and the
+//! clock face catalog.
+
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+
+use crate::error::{Error, Result};
+use crate::models::{
+    ClockFace, ClockFacePage, DivoomDevice, LcdIndependenceInfo, LcdInfo, LcdInfoResponse,
+};
+use crate::protocol::{DialList, DialListRequest, DialTypeList, LanDeviceList, LcdInfoList};
+
+const CLOUD_URL: &str = "https://app.divoom-gz.com";
+const CLOUD_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn device_type(hardware: u64) -> &'static str {
+    match hardware {
+        400 => "Times Gate",
+        401 => "Pixoo 64",
+        402 => "Pixoo 32",
+        403 => "Pixoo 16",
+        404 => "Ditoo",
+        405 => "Ditoo Plus",
+        406 => "Ditoo Pro",
+        407 => "Pixoo Max",
+        408 => "Pixoo Mini",
+        _ => "Unknown Divoom Device",
+    }
+}
+
+async fn parse_json<T: DeserializeOwned>(response: reqwest::Response) -> Result<T> {
+    if !response.status().is_success() {
+        return Err(Error::Status(response.status()));
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|e| Error::InvalidResponse(e.to_string()))
+}
+
+/// Lists the devices the cloud service has seen on the caller's public IP.
+pub async fn discover_lan_devices() -> Result<Vec<DivoomDevice>> {
+    let client = reqwest::Client::builder().timeout(CLOUD_TIMEOUT).build()?;
+    let response = client
+        .post(format!("{}/Device/ReturnSameLANDevice", CLOUD_URL))
+        .send()
+        .await?;
+    let list: LanDeviceList = parse_json(response).await?;
+
+    Ok(list
+        .device_list
+        .into_iter()
+        .map(|device| DivoomDevice {
+            name: device
+                .device_name
+                .unwrap_or_else(|| "Unknown Device".to_string()),
+            mac_address: device.device_mac,
+            device_type: device_type(device.hardware).to_string(),
+            ip_address: device.device_private_ip,
+            signal_strength: None,
+            is_connected: true,
+            device_id: Some(device.device_id),
+        })
+        .collect())
+}
+
+/// Reads the LCD layout (independence groups and their clock faces) of a device.
+pub async fn lcd_info(device_id: u64) -> Result<LcdInfoResponse> {
+    let client = reqwest::Client::builder().timeout(CLOUD_TIMEOUT).build()?;
+    let response = client
+        .get(format!(
+            "{}/Channel/Get5LcdInfoV2?DeviceType=LCD&DeviceId={}",
+            CLOUD_URL, device_id
+        ))
+        .send()
+        .await?;
+    let list: LcdInfoList = parse_json(response).await?;
+
+    Ok(LcdInfoResponse {
+        device_id,
+        independence_list: list
+            .lcd_independence_list
+            .into_iter()
+            .map(|item| LcdIndependenceInfo {
+                lcd_independence: item.lcd_independence,
+                lcd_list: item
+                    .lcd_list
+                    .into_iter()
+                    .map(|lcd| LcdInfo {
+                        lcd_clock_id: lcd.lcd_clock_id,
+                    })
+                    .collect(),
+            })
+            .collect(),
+    })
+}
+
+/// Lists the clock face categories, e.g. `Social` or `Game`.
+pub async fn clock_face_types() -> Result<Vec<String>> {
+    let client = reqwest::Client::builder().timeout(CLOUD_TIMEOUT).build()?;
+    let response = client
+        .post(format!("{}/Channel/GetDialType", CLOUD_URL))
+        .send()
+        .await?;
+    let list: DialTypeList = parse_json(response).await?;
+
+    Ok(list.dial_type_list)
+}
+
+/// Reads one page (starting at 1, 30 faces each) of a clock face category.
+pub async fn clock_faces(dial_type: &str, page: u32) -> Result<ClockFacePage> {
+    let client = reqwest::Client::builder().timeout(CLOUD_TIMEOUT).build()?;
+    let response = client
+        .post(format!("{}/Channel/GetDialList", CLOUD_URL))
+        .json(&DialListRequest {
+            dial_type: dial_type.to_string(),
+            page: page.max(1),
+        })
+        .send()
+        .await?;
+    let list: DialList = parse_json(response).await?;
+
+    Ok(ClockFacePage {
+        total: list.total_num,
+        faces: list
+            .dial_list
+            .into_iter()
+            .map(|dial| ClockFace {
+                clock_id: dial.clock_id,
+                name: dial.name,
+            })
+            .collect(),
+    })
+}
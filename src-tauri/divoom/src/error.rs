@@ -0,0 +1,60 @@
+use std::fmt;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum Error {
+    /// The request could not be sent or the response body could not be read.
+    Http(reqwest::Error),
+    /// The server answered with a non-success HTTP status.
+    Status(reqwest::StatusCode),
+    /// The device answered with a non-zero `error_code`.
+    Device(i64),
+    /// The response was not the JSON the command expects.
+    InvalidResponse(String),
+    Image(image::ImageError),
+    InvalidArgument(String),
+    NotFound(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Http(e) => write!(f, "HTTP request failed: {}", e),
+            Error::Status(status) => write!(f, "Request failed with status: {}", status),
+            Error::Device(code) => write!(f, "Device returned error code {}", code),
+            Error::InvalidResponse(e) => write!(f, "Failed to parse response: {}", e),
+            Error::Image(e) => write!(f, "Failed to encode image: {}", e),
+            Error::InvalidArgument(e) => write!(f, "Invalid argument: {}", e),
+            Error::NotFound(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Http(e) => Some(e),
+            Error::Image(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(e: reqwest::Error) -> Self {
+        Error::Http(e)
+    }
+}
+
+impl From<image::ImageError> for Error {
+    fn from(e: image::ImageError) -> Self {
+        Error::Image(e)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::InvalidResponse(e.to_string())
+    }
+}
@@ -0,0 +1,46 @@
+//! Client for the local HTTP API of Divoom devices (Pixoo, Times Gate, ...).
+//!
+//! ```no_run
+//! # async fn example() -> divoom::Result<()> {
+//! let client = divoom::DivoomClient::new("192.168.1.50");
+//! client.set_brightness(50).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+mod client;
+pub mod cloud;
+mod error;
+mod models;
+pub mod protocol;
+mod text;
+
+pub use client::{CommandObserver, DivoomClient};
+pub use error::{Error, Result};
+pub use models::{
+    BuzzerPattern, BuzzerTiming, Channel, ChannelSelection, ClockFace, ClockFacePage, CloudChannel,
+    DeviceSettings, DisplayItem, DisplayItemKind, DivoomDevice, LcdIndependenceInfo, LcdInfo,
+    LcdInfoResponse, StopwatchAction, TextConfig,
+};
+pub use text::{Color, Font, ScrollDirection, ScrollSpeed, TextAlignment};
+
+/// Width and height of a Times Gate LCD in pixels.
+pub const LCD_SIZE: u32 = 128;
+
+/// Number of LCDs on a Times Gate.
+pub const LCD_COUNT: usize = 5;
+
+/// Most frames one animation upload may have.
+pub const MAX_FRAMES: usize = 60;
+
+/// PicIDs are reset on the device before they would pass this.
+pub const MAX_PIC_ID: u32 = 1000;
+
+/// Highest minute count of the countdown tool, which shows at most 99:59.
+pub const MAX_TIMER_MINUTES: u32 = 99;
+
+/// Highest value the scoreboard tool can show.
+pub const MAX_SCORE: u16 = 999;
+
+/// Clock id of the built-in PC Monitor face.
+pub const PC_MONITOR_CLOCK_ID: u64 = 625;
@@ -0,0 +1,423 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::protocol::AllConf;
+use crate::text::{Color, Font, ScrollDirection, ScrollSpeed, TextAlignment};
+use crate::{LCD_COUNT, LCD_SIZE};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DivoomDevice {
+    pub name: String,
+    pub mac_address: Option<String>,
+    pub device_type: String,
+    pub ip_address: Option<String>,
+    pub signal_strength: Option<i32>,
+    pub is_connected: bool,
+    pub device_id: Option<u64>,
+}
+
+impl DivoomDevice {
+    /// Number of LCDs the device drives; only the Times Gate has more than one.
+    pub fn lcd_count(&self) -> usize {
+        if self.device_type == "Times Gate" {
+            LCD_COUNT
+        } else {
+            1
+        }
+    }
+
+    /// Width and height of one LCD in pixels; unknown types are assumed to have the
+    /// Times Gate's.
+    pub fn resolution(&self) -> u32 {
+        match self.device_type.as_str() {
+            "Pixoo 64" => 64,
+            "Pixoo 32" | "Pixoo Max" => 32,
+            "Pixoo 16" | "Pixoo Mini" | "Ditoo" | "Ditoo Plus" | "Ditoo Pro" => 16,
+            _ => LCD_SIZE,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeviceSettings {
+    pub brightness: Option<u8>,
+    pub rotation_flag: Option<u8>,
+    pub clock_time: Option<u32>,
+    pub gallery_time: Option<u32>,
+    pub single_gallery_time: Option<u32>,
+    pub power_on_channel_id: Option<u8>,
+    pub gallery_show_time_flag: Option<u8>,
+    pub current_clock_id: Option<u64>,
+    pub current_channel: Option<u8>,
+    pub custom_page_index: Option<u8>,
+    pub gyrate_angle: Option<u8>,
+    pub date_format: Option<String>,
+    pub time24_flag: Option<u8>,
+    pub temperature_mode: Option<u8>,
+    pub mirror_flag: Option<u8>,
+    pub light_switch: Option<u8>,
+    pub firmware_version: Option<String>,
+    /// Unix time reported by `Device/GetDeviceTime`.
+    pub device_utc_time: Option<i64>,
+    pub device_local_time: Option<String>,
+    /// `Channel/GetAllConf` keys without a field above.
+    #[serde(default)]
+    pub raw: BTreeMap<String, serde_json::Value>,
+}
+
+impl From<AllConf> for DeviceSettings {
+    fn from(mut conf: AllConf) -> Self {
+        conf.extra.remove("error_code");
+
+        Self {
+            brightness: conf.brightness,
+            rotation_flag: conf.rotation_flag,
+            clock_time: conf.clock_time,
+            gallery_time: conf.gallery_time,
+            single_gallery_time: conf.single_galley_time,
+            power_on_channel_id: conf.power_on_channel_id,
+            gallery_show_time_flag: conf.gallery_show_time_flag,
+            current_clock_id: conf.cur_clock_id,
+            current_channel: conf.select_index,
+            custom_page_index: conf.custom_page_index,
+            gyrate_angle: conf.gyrate_angle,
+            date_format: conf.date_format,
+            time24_flag: conf.time24_flag,
+            temperature_mode: conf.temperature_mode,
+            mirror_flag: conf.mirror_flag,
+            light_switch: conf.light_switch,
+            firmware_version: conf.firmware_version,
+            device_utc_time: None,
+            device_local_time: None,
+            raw: conf.extra,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct TextConfig {
+    pub id: u8,
+    pub content: String,
+    pub x: u8,
+    pub y: u8,
+    pub font: Option<Font>,
+    pub color: Option<Color>,
+    pub alignment: Option<TextAlignment>,
+    pub text_width: Option<u8>,
+    /// Which way text wider than `text_width` scrolls.
+    pub direction: Option<ScrollDirection>,
+    pub speed: Option<ScrollSpeed>,
+}
+
+impl TextConfig {
+    /// Width used when `text_width` is not set.
+    pub const DEFAULT_WIDTH: u8 = 64;
+
+    /// Checks that the text box lies on a square panel of `resolution` pixels.
+    pub fn validate(&self, resolution: u32) -> Result<()> {
+        let width = self.text_width.unwrap_or(Self::DEFAULT_WIDTH);
+        let problem = if u32::from(self.x) >= resolution {
+            format!("x = {} is outside the panel", self.x)
+        } else if u32::from(self.y) >= resolution {
+            format!("y = {} is outside the panel", self.y)
+        } else if width == 0 || u32::from(width) > resolution {
+            format!("text_width = {} must be 1-{}", width, resolution)
+        } else if u32::from(self.x) + u32::from(width) > resolution {
+            format!(
+                "x = {} with text_width = {} runs past the panel edge",
+                self.x, width
+            )
+        } else {
+            return Ok(());
+        };
+
+        Err(Error::InvalidArgument(format!(
+            "text {}: {} ({}x{} pixels)",
+            self.id, problem, resolution, resolution
+        )))
+    }
+}
+
+/// What a `Draw/SendHttpItemList` element shows; everything but `Text` is rendered
+/// and kept up to date by the firmware.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum DisplayItemKind {
+    /// The element's own `content`.
+    #[default]
+    Text,
+    /// `hh:mm`
+    Time,
+    /// `hh:mm:ss`
+    TimeWithSeconds,
+    Year,
+    Day,
+    Month,
+    /// Short weekday name, e.g. `SUN`.
+    Weekday,
+    /// Current temperature at the device's weather location.
+    Temperature,
+    MaxTemperature,
+    MinTemperature,
+    /// Weather condition name, e.g. `Sunny`.
+    Weather,
+    /// Noise meter reading.
+    Noise,
+}
+
+impl DisplayItemKind {
+    pub(crate) fn code(self) -> u8 {
+        match self {
+            DisplayItemKind::Time => 5,
+            DisplayItemKind::TimeWithSeconds => 6,
+            DisplayItemKind::Year => 7,
+            DisplayItemKind::Day => 8,
+            DisplayItemKind::Month => 9,
+            DisplayItemKind::Weekday => 13,
+            DisplayItemKind::Temperature => 17,
+            DisplayItemKind::MaxTemperature => 18,
+            DisplayItemKind::MinTemperature => 19,
+            DisplayItemKind::Weather => 20,
+            DisplayItemKind::Noise => 21,
+            DisplayItemKind::Text => 22,
+        }
+    }
+}
+
+/// One element of a text layout sent with `Draw/SendHttpItemList`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct DisplayItem {
+    /// Position and style; `content` is only shown for [`DisplayItemKind::Text`].
+    #[serde(flatten)]
+    pub text: TextConfig,
+    #[serde(default)]
+    pub kind: DisplayItemKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LcdInfo {
+    pub lcd_clock_id: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LcdIndependenceInfo {
+    pub lcd_independence: u64,
+    pub lcd_list: Vec<LcdInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LcdInfoResponse {
+    pub device_id: u64,
+    pub independence_list: Vec<LcdIndependenceInfo>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StopwatchAction {
+    Stop,
+    Start,
+    Reset,
+}
+
+impl StopwatchAction {
+    pub(crate) fn status(self) -> u8 {
+        match self {
+            StopwatchAction::Stop => 0,
+            StopwatchAction::Start => 1,
+            StopwatchAction::Reset => 2,
+        }
+    }
+}
+
+/// One `Device/PlayBuzzer` call; all times in milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct BuzzerTiming {
+    /// Length of one beep.
+    pub active_ms: u32,
+    /// Pause after each beep.
+    pub off_ms: u32,
+    /// How long the whole pattern plays.
+    pub total_ms: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum BuzzerPattern {
+    ShortBeep,
+    TripleBeep,
+    /// Five seconds of long beeps.
+    Alarm,
+}
+
+impl BuzzerPattern {
+    pub fn timing(self) -> BuzzerTiming {
+        let (active_ms, off_ms, total_ms) = match self {
+            BuzzerPattern::ShortBeep => (200, 0, 200),
+            BuzzerPattern::TripleBeep => (150, 150, 900),
+            BuzzerPattern::Alarm => (500, 500, 5000),
+        };
+
+        BuzzerTiming {
+            active_ms,
+            off_ms,
+            total_ms,
+        }
+    }
+}
+
+/// What the device shows; `Channel/SetIndex` and `SelectIndex` in `Channel/GetAllConf`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Channel {
+    Faces,
+    Cloud,
+    Visualizer,
+    Custom,
+    BlackScreen,
+}
+
+impl Channel {
+    pub(crate) fn index(self) -> u8 {
+        match self {
+            Channel::Faces => 0,
+            Channel::Cloud => 1,
+            Channel::Visualizer => 2,
+            Channel::Custom => 3,
+            Channel::BlackScreen => 4,
+        }
+    }
+
+    pub(crate) fn from_index(index: u8) -> Option<Self> {
+        match index {
+            0 => Some(Channel::Faces),
+            1 => Some(Channel::Cloud),
+            2 => Some(Channel::Visualizer),
+            3 => Some(Channel::Custom),
+            4 => Some(Channel::BlackScreen),
+            _ => None,
+        }
+    }
+}
+
+/// Source of the cloud channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CloudChannel {
+    Recommended,
+    Favourites,
+    Artists,
+}
+
+impl CloudChannel {
+    pub(crate) fn index(self) -> u8 {
+        match self {
+            CloudChannel::Recommended => 0,
+            CloudChannel::Favourites => 1,
+            CloudChannel::Artists => 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChannelSelection {
+    /// `None` when the firmware reports an index this crate does not know.
+    pub channel: Option<Channel>,
+    pub clock_id: Option<u64>,
+    pub custom_page_index: Option<u8>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClockFace {
+    pub clock_id: u64,
+    pub name: String,
+}
+
+/// One page of the cloud clock face catalog.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClockFacePage {
+    /// Number of faces of the requested type over all pages.
+    pub total: u32,
+    pub faces: Vec<ClockFace>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(x: u8, y: u8, text_width: Option<u8>) -> TextConfig {
+        TextConfig {
+            id: 1,
+            content: "CPU".to_string(),
+            x,
+            y,
+            font: None,
+            color: None,
+            alignment: None,
+            text_width,
+            direction: None,
+            speed: None,
+        }
+    }
+
+    #[test]
+    fn stored_text_configs_of_older_versions_still_load() {
+        // Numeric font and alignment, color as "r,g,b", no scroll settings
+        let old = r#"{"id":3,"content":"Hi","x":0,"y":10,"font":2,"color":"255,0,0","alignment":1,"text_width":64}"#;
+        let text: TextConfig = serde_json::from_str(old).unwrap();
+
+        assert_eq!(text.font, Some(Font::Verdana));
+        assert_eq!(text.color, Some(Color::rgb(255, 0, 0)));
+        assert_eq!(text.alignment, Some(TextAlignment::Normal));
+        assert_eq!(text.direction, None);
+        assert_eq!(text.speed, None);
+
+        let hex = r##"{"id":0,"content":"","x":0,"y":0,"font":null,"color":"#00FF00","alignment":null,"text_width":null}"##;
+        let text: TextConfig = serde_json::from_str(hex).unwrap();
+        assert_eq!(text.color, Some(Color::rgb(0, 255, 0)));
+    }
+
+    #[test]
+    fn validate_accepts_texts_inside_the_panel() {
+        assert!(text(0, 0, None).validate(LCD_SIZE).is_ok());
+        assert!(text(127, 127, Some(1)).validate(LCD_SIZE).is_ok());
+        assert!(text(0, 0, Some(128)).validate(LCD_SIZE).is_ok());
+        assert!(text(64, 0, None).validate(LCD_SIZE).is_ok());
+        assert!(text(0, 63, Some(64)).validate(64).is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_texts_outside_the_panel() {
+        assert!(text(128, 0, None).validate(LCD_SIZE).is_err());
+        assert!(text(0, 128, None).validate(LCD_SIZE).is_err());
+        assert!(text(0, 0, Some(0)).validate(LCD_SIZE).is_err());
+        assert!(text(0, 0, Some(129)).validate(LCD_SIZE).is_err());
+        // The default width does not fit a 32 pixel panel
+        assert!(text(0, 0, None).validate(32).is_err());
+        // Each fits on its own, but the box runs past the right edge
+        assert!(text(120, 0, Some(64)).validate(LCD_SIZE).is_err());
+        assert!(text(65, 0, None).validate(LCD_SIZE).is_err());
+
+        let Err(Error::InvalidArgument(message)) = text(120, 0, Some(64)).validate(LCD_SIZE) else {
+            panic!("x = 120 with width 64 was accepted");
+        };
+        assert_eq!(
+            message,
+            "text 1: x = 120 with text_width = 64 runs past the panel edge (128x128 pixels)"
+        );
+
+        let Err(Error::InvalidArgument(message)) = text(64, 0, None).validate(64) else {
+            panic!("x = 64 was accepted on a 64 pixel panel");
+        };
+        assert_eq!(
+            message,
+            "text 1: x = 64 is outside the panel (64x64 pixels)"
+        );
+    }
+}
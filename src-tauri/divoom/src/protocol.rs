@@ -0,0 +1,716 @@
+//! Wire format of the commands sent to `POST http://<ip>/post` and of their answers.
+//!
+//! Field names follow the firmware exactly, which mixes PascalCase with a few
+//! lowercase keys (`x`, `font`, `color`, ...), so every key is spelled out here once.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::LCD_COUNT;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "Command")]
+pub enum Request {
+    #[serde(rename = "Channel/GetAllConf")]
+    GetAllConf,
+    #[serde(rename = "Channel/SetBrightness", rename_all = "PascalCase")]
+    SetBrightness {
+        /// 0-100
+        brightness: u8,
+    },
+    #[serde(rename = "Channel/OnOffScreen")]
+    OnOffScreen {
+        #[serde(rename = "OnOff")]
+        on_off: u8,
+    },
+    #[serde(rename = "Channel/SetIndex", rename_all = "PascalCase")]
+    SetIndex {
+        /// 0 - faces, 1 - cloud, 2 - visualizer, 3 - custom, 4 - black screen
+        select_index: u8,
+    },
+    #[serde(rename = "Channel/SetCustomPageIndex", rename_all = "PascalCase")]
+    SetCustomPageIndex {
+        /// 0-2
+        custom_page_index: u8,
+    },
+    #[serde(rename = "Channel/SetEqPosition", rename_all = "PascalCase")]
+    SetEqPosition {
+        /// Visualizer index in the app's list, starting at 0.
+        eq_position: u8,
+    },
+    #[serde(rename = "Channel/CloudIndex", rename_all = "PascalCase")]
+    CloudIndex {
+        /// 0 - recommended gallery, 1 - favourites, 2 - subscribed artists
+        index: u8,
+    },
+    #[serde(rename = "Channel/SetClockSelectId", rename_all = "PascalCase")]
+    SetClockSelectId {
+        lcd_independence: u64,
+        device_id: u64,
+        lcd_index: u32,
+        clock_id: u64,
+    },
+    #[serde(rename = "Device/SetDisTempMode", rename_all = "PascalCase")]
+    SetDisTempMode {
+        /// 0 - celsius, 1 - fahrenheit
+        mode: u8,
+    },
+    #[serde(rename = "Device/SetMirrorMode", rename_all = "PascalCase")]
+    SetMirrorMode {
+        /// 0 - disable, 1 - enable
+        mode: u8,
+    },
+    #[serde(rename = "Device/SetTime24Flag", rename_all = "PascalCase")]
+    SetTime24Flag {
+        /// 0 - 12 hours, 1 - 24 hours
+        mode: u8,
+    },
+    #[serde(rename = "Device/GetWeatherInfo")]
+    GetWeatherInfo,
+    #[serde(rename = "Device/GetDeviceTime")]
+    GetDeviceTime,
+    #[serde(rename = "Device/SetUTC", rename_all = "PascalCase")]
+    SetUtc {
+        /// Unix time in seconds.
+        utc: i64,
+    },
+    /// Beeps for `active_time_in_cycle`, pauses for `off_time_in_cycle` and repeats
+    /// until `play_total_time` has passed; all times in milliseconds.
+    #[serde(rename = "Device/PlayBuzzer", rename_all = "PascalCase")]
+    PlayBuzzer {
+        active_time_in_cycle: u32,
+        off_time_in_cycle: u32,
+        play_total_time: u32,
+    },
+    #[serde(rename = "Device/SysReboot")]
+    SysReboot,
+    #[serde(rename = "Device/UpdatePCParaInfo", rename_all = "PascalCase")]
+    UpdatePcParaInfo { screen_list: Vec<PcParaScreen> },
+    /// Location used by the device to fetch weather; coordinates are sent as strings.
+    #[serde(rename = "Sys/LogAndLat", rename_all = "PascalCase")]
+    SetLocation { longitude: String, latitude: String },
+    #[serde(rename = "Sys/TimeZone", rename_all = "PascalCase")]
+    SetTimeZone {
+        /// `GMT+3`, `GMT-5`, ...
+        time_zone_value: String,
+    },
+    #[serde(rename = "Tools/SetTimer", rename_all = "PascalCase")]
+    SetTimer {
+        minute: u32,
+        second: u32,
+        /// 1 - start, 0 - stop
+        status: u8,
+    },
+    #[serde(rename = "Tools/SetStopWatch", rename_all = "PascalCase")]
+    SetStopWatch {
+        /// 0 - stop, 1 - start, 2 - reset
+        status: u8,
+    },
+    #[serde(rename = "Tools/SetScoreBoard", rename_all = "PascalCase")]
+    SetScoreBoard {
+        /// 0-999
+        blue_score: u16,
+        /// 0-999
+        red_score: u16,
+    },
+    #[serde(rename = "Tools/SetNoiseStatus", rename_all = "PascalCase")]
+    SetNoiseStatus {
+        /// 1 - start, 0 - stop
+        noise_status: u8,
+    },
+    #[serde(rename = "Draw/SendHttpText")]
+    SendHttpText(HttpText),
+    #[serde(rename = "Draw/SendHttpGif")]
+    SendHttpGif(HttpGif),
+    #[serde(rename = "Draw/GetHttpGifId")]
+    GetHttpGifId,
+    /// Shows every element of `item_list` in one go.
+    #[serde(rename = "Draw/SendHttpItemList")]
+    SendHttpItemList {
+        #[serde(rename = "LcdIndex")]
+        lcd_index: u32,
+        #[serde(rename = "ItemList")]
+        item_list: Vec<HttpItem>,
+    },
+    /// Removes every text element from the LCD.
+    #[serde(rename = "Draw/ClearHttpText")]
+    ClearHttpText {
+        #[serde(rename = "LcdIndex")]
+        lcd_index: u32,
+    },
+    /// Starts the PicID sequence over, so the next upload may use PicID 1.
+    #[serde(rename = "Draw/ResetHttpGifId")]
+    ResetHttpGifId,
+    /// Runs several commands from one request, in order.
+    #[serde(rename = "Draw/CommandList")]
+    CommandList {
+        #[serde(rename = "CommandList")]
+        command_list: Vec<Request>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct PcParaScreen {
+    pub lcd_id: u32,
+    /// Six values in the order the PC Monitor face shows them.
+    pub disp_data: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HttpText {
+    #[serde(rename = "LcdIndex")]
+    pub lcd_index: u32,
+    #[serde(rename = "TextId")]
+    pub text_id: u8,
+    pub x: u8,
+    pub y: u8,
+    pub dir: u8,
+    pub font: u8,
+    #[serde(rename = "TextWidth")]
+    pub text_width: u8,
+    pub speed: u32,
+    #[serde(rename = "TextString")]
+    pub text_string: String,
+    /// `r,g,b` or `#RRGGBB`
+    pub color: String,
+    pub align: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HttpItem {
+    #[serde(rename = "TextId")]
+    pub text_id: u8,
+    /// What the element shows, see `DisplayItemKind`; 22 is `text_string`.
+    #[serde(rename = "type")]
+    pub item_type: u8,
+    pub x: u8,
+    pub y: u8,
+    pub dir: u8,
+    pub font: u8,
+    #[serde(rename = "TextWidth")]
+    pub text_width: u8,
+    /// The firmware spells it with a lowercase "h".
+    #[serde(rename = "Textheight")]
+    pub text_height: u8,
+    pub speed: u32,
+    #[serde(rename = "TextString")]
+    pub text_string: String,
+    pub color: String,
+    pub align: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HttpGif {
+    /// One flag per LCD; 1 shows the picture on that LCD.
+    #[serde(rename = "LCDArray")]
+    pub lcd_array: [u8; LCD_COUNT],
+    #[serde(rename = "PicNum")]
+    pub pic_num: u32,
+    #[serde(rename = "PicWidth")]
+    pub pic_width: u32,
+    #[serde(rename = "PicOffset")]
+    pub pic_offset: u32,
+    #[serde(rename = "PicID")]
+    pub pic_id: u32,
+    #[serde(rename = "PicSpeed")]
+    pub pic_speed: u32,
+    /// Base64 encoded JPEG frame.
+    #[serde(rename = "PicData")]
+    pub pic_data: String,
+}
+
+/// Envelope shared by all device answers.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CommandResponse {
+    #[serde(default)]
+    pub error_code: i64,
+}
+
+/// Answer to [`Request::GetAllConf`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct AllConf {
+    pub brightness: Option<u8>,
+    pub rotation_flag: Option<u8>,
+    /// Seconds a clock face is shown while channels rotate.
+    pub clock_time: Option<u32>,
+    /// Seconds the gallery is shown while channels rotate.
+    pub gallery_time: Option<u32>,
+    /// Seconds per picture in the gallery (the firmware spells it "Galley").
+    pub single_galley_time: Option<u32>,
+    /// Channel shown after power on.
+    pub power_on_channel_id: Option<u8>,
+    pub gallery_show_time_flag: Option<u8>,
+    pub cur_clock_id: Option<u64>,
+    /// Current channel: 0 - faces, 1 - cloud, 2 - visualizer, 3 - custom, 4 - black screen.
+    pub select_index: Option<u8>,
+    pub custom_page_index: Option<u8>,
+    /// Screen rotation: 0 - normal, 1 - 90, 2 - 180, 3 - 270 degrees.
+    pub gyrate_angle: Option<u8>,
+    /// Some firmwares send a format index, others a pattern string.
+    #[serde(default, deserialize_with = "string_or_number")]
+    pub date_format: Option<String>,
+    pub time24_flag: Option<u8>,
+    pub temperature_mode: Option<u8>,
+    pub mirror_flag: Option<u8>,
+    pub light_switch: Option<u8>,
+    #[serde(
+        default,
+        alias = "SoftwareVersion",
+        alias = "Version",
+        deserialize_with = "string_or_number"
+    )]
+    pub firmware_version: Option<String>,
+    /// Keys this model does not know yet, including `error_code`.
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, serde_json::Value>,
+}
+
+/// Answer to [`Request::GetDeviceTime`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DeviceTime {
+    #[serde(rename = "UTCTime")]
+    pub utc_time: Option<i64>,
+    /// `YYYY-MM-DD hh:mm:ss` in the device's time zone.
+    #[serde(rename = "LocalTime")]
+    pub local_time: Option<String>,
+}
+
+/// Answer to [`Request::GetHttpGifId`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HttpGifId {
+    /// Last PicID the device accepted.
+    #[serde(rename = "PicId")]
+    pub pic_id: u32,
+}
+
+/// Answer to [`Request::GetWeatherInfo`]; serialized in snake_case for callers.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all(deserialize = "PascalCase"))]
+pub struct WeatherInfo {
+    /// Condition name, e.g. `Sunny` or `Cloudy`.
+    pub weather: Option<String>,
+    /// Temperatures follow the device's °C/°F setting.
+    pub cur_temp: Option<f64>,
+    pub min_temp: Option<f64>,
+    pub max_temp: Option<f64>,
+    pub pressure: Option<f64>,
+    pub humidity: Option<f64>,
+    pub visibility: Option<f64>,
+    pub wind_speed: Option<f64>,
+}
+
+/// Answer of the cloud `Device/ReturnSameLANDevice` endpoint.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct LanDeviceList {
+    #[serde(default)]
+    pub device_list: Vec<LanDevice>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct LanDevice {
+    pub device_name: Option<String>,
+    #[serde(default)]
+    pub device_id: u64,
+    #[serde(rename = "DevicePrivateIP")]
+    pub device_private_ip: Option<String>,
+    pub device_mac: Option<String>,
+    #[serde(default)]
+    pub hardware: u64,
+}
+
+/// Answer of the cloud `Channel/Get5LcdInfoV2` endpoint.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct LcdInfoList {
+    #[serde(default)]
+    pub lcd_independence_list: Vec<LcdIndependence>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct LcdIndependence {
+    #[serde(default)]
+    pub lcd_independence: u64,
+    #[serde(default)]
+    pub lcd_list: Vec<LcdClock>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct LcdClock {
+    #[serde(default)]
+    pub lcd_clock_id: u64,
+}
+
+/// Answer of the cloud `Channel/GetDialType` endpoint.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DialTypeList {
+    #[serde(default)]
+    pub dial_type_list: Vec<String>,
+}
+
+/// Body of the cloud `Channel/GetDialList` endpoint.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DialListRequest {
+    pub dial_type: String,
+    /// Starts at 1.
+    pub page: u32,
+}
+
+/// Answer of the cloud `Channel/GetDialList` endpoint, one page of clock faces.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DialList {
+    #[serde(default)]
+    pub total_num: u32,
+    #[serde(default)]
+    pub dial_list: Vec<Dial>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Dial {
+    #[serde(default)]
+    pub clock_id: u64,
+    #[serde(default)]
+    pub name: String,
+}
+
+fn string_or_number<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(
+        match Option::<serde_json::Value>::deserialize(deserializer)? {
+            Some(serde_json::Value::String(s)) => Some(s),
+            Some(serde_json::Value::Number(n)) => Some(n.to_string()),
+            _ => None,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    fn http_gif(pic_id: u32, pic_offset: u32) -> HttpGif {
+        HttpGif {
+            lcd_array: [0, 1, 0, 0, 0],
+            pic_num: 2,
+            pic_width: 128,
+            pic_offset,
+            pic_id,
+            pic_speed: 100,
+            pic_data: "/9j/4AAQ".to_string(),
+        }
+    }
+
+    /// Command name of every variant; the match fails to compile when one is added.
+    fn command_name(request: &Request) -> &'static str {
+        match request {
+            Request::GetAllConf => "Channel/GetAllConf",
+            Request::SetBrightness { .. } => "Channel/SetBrightness",
+            Request::OnOffScreen { .. } => "Channel/OnOffScreen",
+            Request::SetIndex { .. } => "Channel/SetIndex",
+            Request::SetCustomPageIndex { .. } => "Channel/SetCustomPageIndex",
+            Request::SetEqPosition { .. } => "Channel/SetEqPosition",
+            Request::CloudIndex { .. } => "Channel/CloudIndex",
+            Request::SetClockSelectId { .. } => "Channel/SetClockSelectId",
+            Request::SetDisTempMode { .. } => "Device/SetDisTempMode",
+            Request::SetMirrorMode { .. } => "Device/SetMirrorMode",
+            Request::SetTime24Flag { .. } => "Device/SetTime24Flag",
+            Request::GetWeatherInfo => "Device/GetWeatherInfo",
+            Request::GetDeviceTime => "Device/GetDeviceTime",
+            Request::SetUtc { .. } => "Device/SetUTC",
+            Request::PlayBuzzer { .. } => "Device/PlayBuzzer",
+            Request::SysReboot => "Device/SysReboot",
+            Request::UpdatePcParaInfo { .. } => "Device/UpdatePCParaInfo",
+            Request::SetLocation { .. } => "Sys/LogAndLat",
+            Request::SetTimeZone { .. } => "Sys/TimeZone",
+            Request::SetTimer { .. } => "Tools/SetTimer",
+            Request::SetStopWatch { .. } => "Tools/SetStopWatch",
+            Request::SetScoreBoard { .. } => "Tools/SetScoreBoard",
+            Request::SetNoiseStatus { .. } => "Tools/SetNoiseStatus",
+            Request::SendHttpText(_) => "Draw/SendHttpText",
+            Request::SendHttpGif(_) => "Draw/SendHttpGif",
+            Request::GetHttpGifId => "Draw/GetHttpGifId",
+            Request::SendHttpItemList { .. } => "Draw/SendHttpItemList",
+            Request::ClearHttpText { .. } => "Draw/ClearHttpText",
+            Request::ResetHttpGifId => "Draw/ResetHttpGifId",
+            Request::CommandList { .. } => "Draw/CommandList",
+        }
+    }
+
+    fn all_requests() -> Vec<Request> {
+        vec![
+            Request::GetAllConf,
+            Request::SetBrightness { brightness: 50 },
+            Request::OnOffScreen { on_off: 1 },
+            Request::SetIndex { select_index: 2 },
+            Request::SetCustomPageIndex {
+                custom_page_index: 1,
+            },
+            Request::SetEqPosition { eq_position: 3 },
+            Request::CloudIndex { index: 1 },
+            Request::SetClockSelectId {
+                lcd_independence: 1,
+                device_id: 300000001,
+                lcd_index: 2,
+                clock_id: 625,
+            },
+            Request::SetDisTempMode { mode: 1 },
+            Request::SetMirrorMode { mode: 0 },
+            Request::SetTime24Flag { mode: 1 },
+            Request::GetWeatherInfo,
+            Request::GetDeviceTime,
+            Request::SetUtc { utc: 1_700_000_000 },
+            Request::PlayBuzzer {
+                active_time_in_cycle: 500,
+                off_time_in_cycle: 500,
+                play_total_time: 3000,
+            },
+            Request::SysReboot,
+            Request::UpdatePcParaInfo {
+                screen_list: vec![PcParaScreen {
+                    lcd_id: 2,
+                    disp_data: vec!["10%".to_string(); 6],
+                }],
+            },
+            Request::SetLocation {
+                longitude: "37.62".to_string(),
+                latitude: "55.75".to_string(),
+            },
+            Request::SetTimeZone {
+                time_zone_value: "GMT+3".to_string(),
+            },
+            Request::SetTimer {
+                minute: 1,
+                second: 30,
+                status: 1,
+            },
+            Request::SetStopWatch { status: 2 },
+            Request::SetScoreBoard {
+                blue_score: 3,
+                red_score: 2,
+            },
+            Request::SetNoiseStatus { noise_status: 1 },
+            Request::SendHttpText(HttpText {
+                lcd_index: 0,
+                text_id: 1,
+                x: 0,
+                y: 40,
+                dir: 0,
+                font: 4,
+                text_width: 56,
+                speed: 10,
+                text_string: "hello, Divoom".to_string(),
+                color: "#FFFF00".to_string(),
+                align: 1,
+            }),
+            Request::SendHttpGif(http_gif(3, 0)),
+            Request::GetHttpGifId,
+            Request::SendHttpItemList {
+                lcd_index: 1,
+                item_list: Vec::new(),
+            },
+            Request::ClearHttpText { lcd_index: 1 },
+            Request::ResetHttpGifId,
+            Request::CommandList {
+                command_list: vec![Request::ResetHttpGifId],
+            },
+        ]
+    }
+
+    #[test]
+    fn every_request_carries_its_command_tag_and_round_trips() {
+        for request in all_requests() {
+            let value = serde_json::to_value(&request).unwrap();
+            assert_eq!(value["Command"], command_name(&request), "{:?}", request);
+
+            let parsed: Request = serde_json::from_value(value).unwrap();
+            assert_eq!(parsed, request);
+        }
+    }
+
+    #[test]
+    fn send_http_gif_matches_firmware_payload() {
+        let captured = json!({
+            "Command": "Draw/SendHttpGif",
+            "LCDArray": [0, 1, 0, 0, 0],
+            "PicNum": 2,
+            "PicWidth": 128,
+            "PicOffset": 1,
+            "PicID": 7,
+            "PicSpeed": 100,
+            "PicData": "/9j/4AAQ"
+        });
+
+        let request = Request::SendHttpGif(http_gif(7, 1));
+        assert_eq!(serde_json::to_value(&request).unwrap(), captured);
+        assert_eq!(
+            serde_json::from_value::<Request>(captured).unwrap(),
+            request
+        );
+    }
+
+    #[test]
+    fn command_list_nests_tagged_commands() {
+        let captured = json!({
+            "Command": "Draw/CommandList",
+            "CommandList": [
+                {"Command": "Channel/SetBrightness", "Brightness": 80},
+                {"Command": "Draw/ClearHttpText", "LcdIndex": 3}
+            ]
+        });
+
+        let request = Request::CommandList {
+            command_list: vec![
+                Request::SetBrightness { brightness: 80 },
+                Request::ClearHttpText { lcd_index: 3 },
+            ],
+        };
+        assert_eq!(serde_json::to_value(&request).unwrap(), captured);
+        assert_eq!(
+            serde_json::from_value::<Request>(captured).unwrap(),
+            request
+        );
+    }
+
+    #[test]
+    fn get_http_gif_id_answer_round_trips() {
+        let captured = json!({"error_code": 0, "PicId": 42});
+
+        let answer: HttpGifId = serde_json::from_value(captured).unwrap();
+        assert_eq!(answer.pic_id, 42);
+        assert_eq!(serde_json::to_value(&answer).unwrap(), json!({"PicId": 42}));
+        assert_eq!(
+            serde_json::to_value(Request::GetHttpGifId).unwrap(),
+            json!({"Command": "Draw/GetHttpGifId"})
+        );
+    }
+
+    #[test]
+    fn all_conf_keeps_unknown_keys_and_reads_version_aliases() {
+        let captured = json!({
+            "error_code": 0,
+            "Brightness": 80,
+            "RotationFlag": 1,
+            "ClockTime": 60,
+            "GalleryTime": 60,
+            "SingleGalleyTime": 5,
+            "PowerOnChannelId": 1,
+            "GalleryShowTimeFlag": 1,
+            "CurClockId": 625,
+            "SelectIndex": 0,
+            "CustomPageIndex": 2,
+            "GyrateAngle": 0,
+            "DateFormat": 1,
+            "Time24Flag": 1,
+            "TemperatureMode": 0,
+            "MirrorFlag": 0,
+            "LightSwitch": 1,
+            "SoftwareVersion": 90102,
+            "DeviceId": 300000001
+        });
+
+        let conf: AllConf = serde_json::from_value(captured).unwrap();
+        assert_eq!(conf.brightness, Some(80));
+        assert_eq!(conf.single_galley_time, Some(5));
+        assert_eq!(conf.date_format.as_deref(), Some("1"));
+        assert_eq!(conf.firmware_version.as_deref(), Some("90102"));
+        assert_eq!(conf.extra.get("error_code"), Some(&json!(0)));
+        assert_eq!(conf.extra.get("DeviceId"), Some(&json!(300000001)));
+        assert!(!conf.extra.contains_key("SoftwareVersion"));
+
+        let written = serde_json::to_value(&conf).unwrap();
+        assert_eq!(written["FirmwareVersion"], "90102");
+        assert_eq!(written["DeviceId"], 300000001);
+        assert_eq!(serde_json::from_value::<AllConf>(written).unwrap(), conf);
+
+        for key in ["Version", "FirmwareVersion"] {
+            let conf: AllConf = serde_json::from_value(json!({ key: "1.2.3" })).unwrap();
+            assert_eq!(conf.firmware_version.as_deref(), Some("1.2.3"), "{}", key);
+        }
+    }
+
+    #[test]
+    fn device_answers_parse_captured_payloads() {
+        let time: DeviceTime = serde_json::from_value(json!({
+            "error_code": 0,
+            "UTCTime": 1700000000,
+            "LocalTime": "2023-11-15 01:13:20"
+        }))
+        .unwrap();
+        assert_eq!(time.utc_time, Some(1_700_000_000));
+        assert_eq!(time.local_time.as_deref(), Some("2023-11-15 01:13:20"));
+
+        let weather: WeatherInfo = serde_json::from_value(json!({
+            "error_code": 0,
+            "Weather": "Cloudy",
+            "CurTemp": 21.5,
+            "MinTemp": 15,
+            "MaxTemp": 24,
+            "Pressure": 1012,
+            "Humidity": 48,
+            "Visibility": 10000,
+            "WindSpeed": 3.4
+        }))
+        .unwrap();
+        assert_eq!(weather.weather.as_deref(), Some("Cloudy"));
+        assert_eq!(weather.cur_temp, Some(21.5));
+        assert_eq!(weather.min_temp, Some(15.0));
+
+        // Callers get snake_case keys back
+        let written = serde_json::to_value(&weather).unwrap();
+        assert_eq!(written["cur_temp"], 21.5);
+        assert_eq!(written["wind_speed"], 3.4);
+
+        let failed: CommandResponse = serde_json::from_value(json!({"error_code": 1})).unwrap();
+        assert_eq!(failed.error_code, 1);
+        let ok: CommandResponse = serde_json::from_value(json!({})).unwrap();
+        assert_eq!(ok.error_code, 0);
+    }
+
+    #[test]
+    fn cloud_answers_parse_captured_payloads() {
+        let devices: LanDeviceList = serde_json::from_value(json!({
+            "ReturnCode": 0,
+            "DeviceList": [{
+                "DeviceName": "Times Gate",
+                "DeviceId": 300000001,
+                "DevicePrivateIP": "192.168.1.50",
+                "DeviceMac": "a0b1c2d3e4f5",
+                "Hardware": 400
+            }]
+        }))
+        .unwrap();
+        let device = &devices.device_list[0];
+        assert_eq!(device.device_private_ip.as_deref(), Some("192.168.1.50"));
+        assert_eq!(device.hardware, 400);
+
+        let lcds: LcdInfoList = serde_json::from_value(json!({
+            "ReturnCode": 0,
+            "LcdIndependenceList": [{
+                "LcdIndependence": 1,
+                "LcdList": [{"LcdClockId": 625}, {"LcdClockId": 12}]
+            }]
+        }))
+        .unwrap();
+        assert_eq!(lcds.lcd_independence_list[0].lcd_list[1].lcd_clock_id, 12);
+
+        let value: Value = serde_json::to_value(&lcds).unwrap();
+        assert_eq!(
+            value["LcdIndependenceList"][0]["LcdList"][0]["LcdClockId"],
+            625
+        );
+    }
+}
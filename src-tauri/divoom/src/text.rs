@@ -0,0 +1,665 @@
+//! Typed style values of a text element: color, font, alignment and scrolling.
+//!
+//! Fonts, alignments and directions are written as snake_case names but also read
+//! from the firmware's numbers, which older settings and the editor still send.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::{Error, Result};
+
+/// Text color; serialized as `#RRGGBB`.
+///
+/// Parsed from `#RRGGBB`, `#RGB`, `r,g,b`, `rgb(r, g, b)`, an `[r, g, b]` array or
+/// a CSS color name such as `orange`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Color {
+    pub const WHITE: Color = Color::rgb(255, 255, 255);
+
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+
+    const fn from_hex(hex: u32) -> Self {
+        Self::rgb((hex >> 16) as u8, (hex >> 8) as u8, hex as u8)
+    }
+}
+
+impl From<[u8; 3]> for Color {
+    fn from([r, g, b]: [u8; 3]) -> Self {
+        Self { r, g, b }
+    }
+}
+
+impl From<Color> for [u8; 3] {
+    fn from(color: Color) -> Self {
+        [color.r, color.g, color.b]
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02X}{:02X}{:02X}", self.r, self.g, self.b)
+    }
+}
+
+impl FromStr for Color {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let value = s.trim().to_ascii_lowercase();
+        let invalid = || {
+            Error::InvalidArgument(format!(
+                "invalid color '{}', expected #RRGGBB, #RGB, r,g,b or a CSS color name",
+                s
+            ))
+        };
+
+        if let Some(hex) = value.strip_prefix('#') {
+            if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(invalid());
+            }
+            let digits = match hex.len() {
+                6 => hex.to_string(),
+                3 => hex.chars().flat_map(|c| [c, c]).collect(),
+                _ => return Err(invalid()),
+            };
+            let hex = u32::from_str_radix(&digits, 16).map_err(|_| invalid())?;
+            return Ok(Color::from_hex(hex));
+        }
+
+        let tuple = value
+            .strip_prefix("rgb(")
+            .and_then(|rest| rest.strip_suffix(')'))
+            .unwrap_or(&value);
+        if tuple.contains(',') {
+            let parts: Vec<u8> = tuple
+                .split(',')
+                .map(|part| part.trim().parse::<u8>())
+                .collect::<std::result::Result<_, _>>()
+                .map_err(|_| invalid())?;
+            return match parts.as_slice() {
+                [r, g, b] => Ok(Color::rgb(*r, *g, *b)),
+                _ => Err(invalid()),
+            };
+        }
+
+        CSS_COLORS
+            .iter()
+            .find(|(name, _)| *name == value)
+            .map(|(_, hex)| Color::from_hex(*hex))
+            .ok_or_else(invalid)
+    }
+}
+
+impl Serialize for Color {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Rgb([u8; 3]),
+            Text(String),
+        }
+
+        match Repr::deserialize(deserializer)? {
+            Repr::Rgb(rgb) => Ok(Color::from(rgb)),
+            Repr::Text(text) => text.parse().map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for Color {
+    fn schema_name() -> String {
+        "Color".to_string()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        String::json_schema(gen)
+    }
+}
+
+/// Values with a snake_case name and a firmware number, both accepted when parsing.
+trait Named: Copy + 'static {
+    /// What the value is, for error messages.
+    const KIND: &'static str;
+    const ALL: &'static [Self];
+
+    fn name(self) -> &'static str;
+    fn number(self) -> u32;
+
+    fn from_number(number: u32) -> Result<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|value| value.number() == number)
+            .ok_or_else(|| Self::unknown(&number.to_string()))
+    }
+
+    fn from_name(name: &str) -> Result<Self> {
+        if let Ok(number) = name.trim().parse() {
+            return Self::from_number(number);
+        }
+
+        let normalized = name.trim().to_ascii_lowercase().replace([' ', '-'], "_");
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|value| value.name() == normalized)
+            .ok_or_else(|| Self::unknown(name))
+    }
+
+    fn unknown(given: &str) -> Error {
+        let known: Vec<String> = Self::ALL
+            .iter()
+            .map(|value| format!("{} ({})", value.name(), value.number()))
+            .collect();
+        Error::InvalidArgument(format!(
+            "unknown {} '{}', expected one of: {}",
+            Self::KIND,
+            given,
+            known.join(", ")
+        ))
+    }
+}
+
+/// Serializes a [`Named`] type by name and parses it from a name or a number.
+macro_rules! named_impls {
+    ($($ty:ty),*) => {$(
+        impl fmt::Display for $ty {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.name())
+            }
+        }
+
+        impl FromStr for $ty {
+            type Err = Error;
+
+            fn from_str(s: &str) -> Result<Self> {
+                Self::from_name(s)
+            }
+        }
+
+        impl Serialize for $ty {
+            fn serialize<S: Serializer>(
+                &self,
+                serializer: S,
+            ) -> std::result::Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.name())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $ty {
+            fn deserialize<D: Deserializer<'de>>(
+                deserializer: D,
+            ) -> std::result::Result<Self, D::Error> {
+                #[derive(Deserialize)]
+                #[serde(untagged)]
+                enum Repr {
+                    Number(u32),
+                    Name(String),
+                }
+
+                match Repr::deserialize(deserializer)? {
+                    Repr::Number(number) => Self::from_number(number),
+                    Repr::Name(name) => Self::from_name(&name),
+                }
+                .map_err(serde::de::Error::custom)
+            }
+        }
+    )*};
+}
+
+named_impls!(Font, TextAlignment, ScrollDirection, ScrollSpeed);
+
+/// Fonts built into the firmware.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "schemars",
+    derive(schemars::JsonSchema),
+    schemars(rename_all = "snake_case")
+)]
+pub enum Font {
+    Arial,
+    TimesNewRoman,
+    Verdana,
+    CourierNew,
+    Georgia,
+    Garamond,
+    ComicSansMs,
+    Impact,
+}
+
+impl Font {
+    pub fn code(self) -> u8 {
+        match self {
+            Font::Arial => 0,
+            Font::TimesNewRoman => 1,
+            Font::Verdana => 2,
+            Font::CourierNew => 3,
+            Font::Georgia => 4,
+            Font::Garamond => 5,
+            Font::ComicSansMs => 6,
+            Font::Impact => 7,
+        }
+    }
+}
+
+impl Named for Font {
+    const KIND: &'static str = "font";
+    const ALL: &'static [Self] = &[
+        Font::Arial,
+        Font::TimesNewRoman,
+        Font::Verdana,
+        Font::CourierNew,
+        Font::Georgia,
+        Font::Garamond,
+        Font::ComicSansMs,
+        Font::Impact,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Font::Arial => "arial",
+            Font::TimesNewRoman => "times_new_roman",
+            Font::Verdana => "verdana",
+            Font::CourierNew => "courier_new",
+            Font::Georgia => "georgia",
+            Font::Garamond => "garamond",
+            Font::ComicSansMs => "comic_sans_ms",
+            Font::Impact => "impact",
+        }
+    }
+
+    fn number(self) -> u32 {
+        self.code().into()
+    }
+}
+
+/// Placement of the text inside its `text_width`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "schemars",
+    derive(schemars::JsonSchema),
+    schemars(rename_all = "snake_case")
+)]
+pub enum TextAlignment {
+    /// Scrolls text that is wider than `text_width`.
+    Scroll,
+    Normal,
+    Middle,
+    Right,
+    Left,
+}
+
+impl TextAlignment {
+    pub fn code(self) -> u8 {
+        match self {
+            TextAlignment::Scroll => 0,
+            TextAlignment::Normal => 1,
+            TextAlignment::Middle => 2,
+            TextAlignment::Right => 3,
+            TextAlignment::Left => 4,
+        }
+    }
+}
+
+impl Named for TextAlignment {
+    const KIND: &'static str = "alignment";
+    const ALL: &'static [Self] = &[
+        TextAlignment::Scroll,
+        TextAlignment::Normal,
+        TextAlignment::Middle,
+        TextAlignment::Right,
+        TextAlignment::Left,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            TextAlignment::Scroll => "scroll",
+            TextAlignment::Normal => "normal",
+            TextAlignment::Middle => "middle",
+            TextAlignment::Right => "right",
+            TextAlignment::Left => "left",
+        }
+    }
+
+    fn number(self) -> u32 {
+        self.code().into()
+    }
+}
+
+/// Which way scrolling text moves.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "schemars",
+    derive(schemars::JsonSchema),
+    schemars(rename_all = "snake_case")
+)]
+pub enum ScrollDirection {
+    #[default]
+    Left,
+    Right,
+}
+
+impl ScrollDirection {
+    pub fn code(self) -> u8 {
+        match self {
+            ScrollDirection::Left => 0,
+            ScrollDirection::Right => 1,
+        }
+    }
+}
+
+impl Named for ScrollDirection {
+    const KIND: &'static str = "scroll direction";
+    const ALL: &'static [Self] = &[ScrollDirection::Left, ScrollDirection::Right];
+
+    fn name(self) -> &'static str {
+        match self {
+            ScrollDirection::Left => "left",
+            ScrollDirection::Right => "right",
+        }
+    }
+
+    fn number(self) -> u32 {
+        self.code().into()
+    }
+}
+
+/// How fast scrolling text moves; its number is the firmware's delay per step.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "schemars",
+    derive(schemars::JsonSchema),
+    schemars(rename_all = "snake_case")
+)]
+pub enum ScrollSpeed {
+    Slow,
+    #[default]
+    Normal,
+    Fast,
+}
+
+impl ScrollSpeed {
+    /// Delay between two scroll steps in milliseconds.
+    pub fn step_ms(self) -> u32 {
+        match self {
+            ScrollSpeed::Slow => 200,
+            ScrollSpeed::Normal => 100,
+            ScrollSpeed::Fast => 50,
+        }
+    }
+}
+
+impl Named for ScrollSpeed {
+    const KIND: &'static str = "scroll speed";
+    const ALL: &'static [Self] = &[ScrollSpeed::Slow, ScrollSpeed::Normal, ScrollSpeed::Fast];
+
+    fn name(self) -> &'static str {
+        match self {
+            ScrollSpeed::Slow => "slow",
+            ScrollSpeed::Normal => "normal",
+            ScrollSpeed::Fast => "fast",
+        }
+    }
+
+    fn number(self) -> u32 {
+        self.step_ms()
+    }
+}
+
+/// CSS named colors (CSS Color Module Level 4).
+const CSS_COLORS: &[(&str, u32)] = &[
+    ("aliceblue", 0xF0F8FF),
+    ("antiquewhite", 0xFAEBD7),
+    ("aqua", 0x00FFFF),
+    ("aquamarine", 0x7FFFD4),
+    ("azure", 0xF0FFFF),
+    ("beige", 0xF5F5DC),
+    ("bisque", 0xFFE4C4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xFFEBCD),
+    ("blue", 0x0000FF),
+    ("blueviolet", 0x8A2BE2),
+    ("brown", 0xA52A2A),
+    ("burlywood", 0xDEB887),
+    ("cadetblue", 0x5F9EA0),
+    ("chartreuse", 0x7FFF00),
+    ("chocolate", 0xD2691E),
+    ("coral", 0xFF7F50),
+    ("cornflowerblue", 0x6495ED),
+    ("cornsilk", 0xFFF8DC),
+    ("crimson", 0xDC143C),
+    ("cyan", 0x00FFFF),
+    ("darkblue", 0x00008B),
+    ("darkcyan", 0x008B8B),
+    ("darkgoldenrod", 0xB8860B),
+    ("darkgray", 0xA9A9A9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xA9A9A9),
+    ("darkkhaki", 0xBDB76B),
+    ("darkmagenta", 0x8B008B),
+    ("darkolivegreen", 0x556B2F),
+    ("darkorange", 0xFF8C00),
+    ("darkorchid", 0x9932CC),
+    ("darkred", 0x8B0000),
+    ("darksalmon", 0xE9967A),
+    ("darkseagreen", 0x8FBC8F),
+    ("darkslateblue", 0x483D8B),
+    ("darkslategray", 0x2F4F4F),
+    ("darkslategrey", 0x2F4F4F),
+    ("darkturquoise", 0x00CED1),
+    ("darkviolet", 0x9400D3),
+    ("deeppink", 0xFF1493),
+    ("deepskyblue", 0x00BFFF),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1E90FF),
+    ("firebrick", 0xB22222),
+    ("floralwhite", 0xFFFAF0),
+    ("forestgreen", 0x228B22),
+    ("fuchsia", 0xFF00FF),
+    ("gainsboro", 0xDCDCDC),
+    ("ghostwhite", 0xF8F8FF),
+    ("gold", 0xFFD700),
+    ("goldenrod", 0xDAA520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xADFF2F),
+    ("grey", 0x808080),
+    ("honeydew", 0xF0FFF0),
+    ("hotpink", 0xFF69B4),
+    ("indianred", 0xCD5C5C),
+    ("indigo", 0x4B0082),
+    ("ivory", 0xFFFFF0),
+    ("khaki", 0xF0E68C),
+    ("lavender", 0xE6E6FA),
+    ("lavenderblush", 0xFFF0F5),
+    ("lawngreen", 0x7CFC00),
+    ("lemonchiffon", 0xFFFACD),
+    ("lightblue", 0xADD8E6),
+    ("lightcoral", 0xF08080),
+    ("lightcyan", 0xE0FFFF),
+    ("lightgoldenrodyellow", 0xFAFAD2),
+    ("lightgray", 0xD3D3D3),
+    ("lightgreen", 0x90EE90),
+    ("lightgrey", 0xD3D3D3),
+    ("lightpink", 0xFFB6C1),
+    ("lightsalmon", 0xFFA07A),
+    ("lightseagreen", 0x20B2AA),
+    ("lightskyblue", 0x87CEFA),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xB0C4DE),
+    ("lightyellow", 0xFFFFE0),
+    ("lime", 0x00FF00),
+    ("limegreen", 0x32CD32),
+    ("linen", 0xFAF0E6),
+    ("magenta", 0xFF00FF),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66CDAA),
+    ("mediumblue", 0x0000CD),
+    ("mediumorchid", 0xBA55D3),
+    ("mediumpurple", 0x9370DB),
+    ("mediumseagreen", 0x3CB371),
+    ("mediumslateblue", 0x7B68EE),
+    ("mediumspringgreen", 0x00FA9A),
+    ("mediumturquoise", 0x48D1CC),
+    ("mediumvioletred", 0xC71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xF5FFFA),
+    ("mistyrose", 0xFFE4E1),
+    ("moccasin", 0xFFE4B5),
+    ("navajowhite", 0xFFDEAD),
+    ("navy", 0x000080),
+    ("oldlace", 0xFDF5E6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6B8E23),
+    ("orange", 0xFFA500),
+    ("orangered", 0xFF4500),
+    ("orchid", 0xDA70D6),
+    ("palegoldenrod", 0xEEE8AA),
+    ("palegreen", 0x98FB98),
+    ("paleturquoise", 0xAFEEEE),
+    ("palevioletred", 0xDB7093),
+    ("papayawhip", 0xFFEFD5),
+    ("peachpuff", 0xFFDAB9),
+    ("peru", 0xCD853F),
+    ("pink", 0xFFC0CB),
+    ("plum", 0xDDA0DD),
+    ("powderblue", 0xB0E0E6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xFF0000),
+    ("rosybrown", 0xBC8F8F),
+    ("royalblue", 0x4169E1),
+    ("saddlebrown", 0x8B4513),
+    ("salmon", 0xFA8072),
+    ("sandybrown", 0xF4A460),
+    ("seagreen", 0x2E8B57),
+    ("seashell", 0xFFF5EE),
+    ("sienna", 0xA0522D),
+    ("silver", 0xC0C0C0),
+    ("skyblue", 0x87CEEB),
+    ("slateblue", 0x6A5ACD),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xFFFAFA),
+    ("springgreen", 0x00FF7F),
+    ("steelblue", 0x4682B4),
+    ("tan", 0xD2B48C),
+    ("teal", 0x008080),
+    ("thistle", 0xD8BFD8),
+    ("tomato", 0xFF6347),
+    ("turquoise", 0x40E0D0),
+    ("violet", 0xEE82EE),
+    ("wheat", 0xF5DEB3),
+    ("white", 0xFFFFFF),
+    ("whitesmoke", 0xF5F5F5),
+    ("yellow", 0xFFFF00),
+    ("yellowgreen", 0x9ACD32),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colors_parse_from_every_notation() {
+        let orange = Color::rgb(255, 165, 0);
+
+        for input in [
+            "#FFA500",
+            "#ffa500",
+            " #FfA500 ",
+            "orange",
+            "Orange",
+            "255,165,0",
+        ] {
+            assert_eq!(input.parse::<Color>().unwrap(), orange, "{}", input);
+        }
+        assert_eq!("rgb(255, 165, 0)".parse::<Color>().unwrap(), orange);
+        assert_eq!("#F80".parse::<Color>().unwrap(), Color::rgb(255, 136, 0));
+        assert_eq!(orange.to_string(), "#FFA500");
+    }
+
+    #[test]
+    fn invalid_colors_are_rejected() {
+        for input in [
+            "", "#FFA50", "#GGGGGG", "256,0,0", "1,2", "1,2,3,4", "nocolor",
+        ] {
+            assert!(
+                matches!(input.parse::<Color>(), Err(Error::InvalidArgument(_))),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn colors_deserialize_from_strings_and_arrays() {
+        let colors: Vec<Color> =
+            serde_json::from_str(r##"["#FF0000", "0,255,0", "blue", [1, 2, 3]]"##).unwrap();
+
+        assert_eq!(
+            colors,
+            vec![
+                Color::rgb(255, 0, 0),
+                Color::rgb(0, 255, 0),
+                Color::rgb(0, 0, 255),
+                Color::rgb(1, 2, 3),
+            ]
+        );
+        assert_eq!(serde_json::to_string(&colors[3]).unwrap(), r##""#010203""##);
+        assert!(serde_json::from_str::<Color>("[256, 0, 0]").is_err());
+    }
+
+    #[test]
+    fn named_values_parse_from_names_and_numbers() {
+        assert_eq!("comic sans ms".parse::<Font>().unwrap(), Font::ComicSansMs);
+        assert_eq!("Courier-New".parse::<Font>().unwrap(), Font::CourierNew);
+        assert_eq!("7".parse::<Font>().unwrap(), Font::Impact);
+        assert_eq!(
+            "middle".parse::<TextAlignment>().unwrap(),
+            TextAlignment::Middle
+        );
+        assert_eq!("fast".parse::<ScrollSpeed>().unwrap(), ScrollSpeed::Fast);
+        assert_eq!("200".parse::<ScrollSpeed>().unwrap(), ScrollSpeed::Slow);
+
+        assert_eq!(serde_json::from_str::<Font>("3").unwrap(), Font::CourierNew);
+        assert_eq!(
+            serde_json::to_string(&Font::TimesNewRoman).unwrap(),
+            r#""times_new_roman""#
+        );
+    }
+
+    #[test]
+    fn out_of_range_values_list_the_known_ones() {
+        let Err(Error::InvalidArgument(message)) = "8".parse::<Font>() else {
+            panic!("font 8 was accepted");
+        };
+        assert!(message.contains("unknown font '8'"), "{}", message);
+        assert!(message.contains("impact (7)"), "{}", message);
+
+        // Speeds are step lengths in milliseconds, not indexes
+        assert!("1".parse::<ScrollSpeed>().is_err());
+        assert!("warp".parse::<ScrollSpeed>().is_err());
+        assert!(serde_json::from_str::<TextAlignment>("5").is_err());
+        assert!(serde_json::from_str::<ScrollDirection>(r#""up""#).is_err());
+    }
+}
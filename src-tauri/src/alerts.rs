@@ -0,0 +1,455 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::app_settings;
+use crate::device_commands;
+use crate::draw_commands;
+use crate::metric_pipeline;
+use crate::metric_providers;
+use crate::models::{BuzzerPattern, Color, TextAlignment, TextConfig, ALERT_TEXT_ID};
+use crate::runtime;
+
+const EVALUATION_INTERVAL: Duration = Duration::from_secs(2);
+const FLASH_INTERVAL: Duration = Duration::from_millis(500);
+// A flash keeps the LCD busy for a second each time
+const MAX_FLASHES: u32 = 20;
+
+static LISTENER: OnceLock<AlertListener> = OnceLock::new();
+static STARTED: AtomicBool = AtomicBool::new(false);
+static RULE_STATES: Mutex<BTreeMap<String, RuleState>> = Mutex::new(BTreeMap::new());
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Comparison {
+    Above,
+    Below,
+}
+
+impl Comparison {
+    fn breached(self, value: f64, threshold: f64) -> bool {
+        match self {
+            Comparison::Above => value > threshold,
+            Comparison::Below => value < threshold,
+        }
+    }
+
+    /// The alert only clears once the value is back past the threshold by `hysteresis`.
+    fn cleared(self, value: f64, threshold: f64, hysteresis: f64) -> bool {
+        match self {
+            Comparison::Above => value <= threshold - hysteresis,
+            Comparison::Below => value >= threshold + hysteresis,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AlertAction {
+    /// Alternates a solid color with black `times` times (at most 20), then leaves the
+    /// color on.
+    Flash {
+        ip_address: String,
+        lcd_index: u32,
+        color: Color,
+        times: u32,
+    },
+    ShowText {
+        ip_address: String,
+        lcd_index: u32,
+        text: String,
+        color: Option<Color>,
+    },
+    /// `source` is an http(s) URL or a local file path.
+    ShowImage {
+        ip_address: String,
+        lcd_index: u32,
+        source: String,
+    },
+    SetBrightness {
+        ip_address: String,
+        brightness: u8,
+    },
+    PlayBuzzer {
+        ip_address: String,
+        pattern: BuzzerPattern,
+    },
+    /// Only notifies the alert listener, e.g. the `metric-alert` event of the frontend.
+    Notify,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertRule {
+    pub id: String,
+    pub name: String,
+    /// Any metric name understood by the pipeline, e.g. `gpu_temperature` or a custom slot.
+    pub metric: String,
+    pub comparison: Comparison,
+    pub threshold: f64,
+    /// How long the threshold must stay breached before the rule fires.
+    #[serde(default)]
+    pub duration_secs: u64,
+    #[serde(default)]
+    pub hysteresis: f64,
+    /// Minimum time between two firings of the same rule.
+    #[serde(default)]
+    pub cooldown_secs: u64,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub actions: Vec<AlertAction>,
+    /// Run once the alert clears, e.g. to restore brightness.
+    #[serde(default)]
+    pub clear_actions: Vec<AlertAction>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertState {
+    Triggered,
+    Cleared,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AlertEvent {
+    pub rule_id: String,
+    pub name: String,
+    pub metric: String,
+    pub value: f64,
+    pub threshold: f64,
+    pub state: AlertState,
+}
+
+#[derive(Default)]
+struct RuleState {
+    breached_since: Option<Instant>,
+    active: bool,
+    last_fired: Option<Instant>,
+}
+
+fn evaluate(
+    rule: &AlertRule,
+    state: &mut RuleState,
+    value: f64,
+    now: Instant,
+) -> Option<AlertState> {
+    if state.active {
+        if rule
+            .comparison
+            .cleared(value, rule.threshold, rule.hysteresis)
+        {
+            state.active = false;
+            state.breached_since = None;
+            return Some(AlertState::Cleared);
+        }
+        return None;
+    }
+
+    if !rule.comparison.breached(value, rule.threshold) {
+        state.breached_since = None;
+        return None;
+    }
+
+    let since = *state.breached_since.get_or_insert(now);
+    if now.duration_since(since) < Duration::from_secs(rule.duration_secs) {
+        return None;
+    }
+
+    let cooling_down = state
+        .last_fired
+        .is_some_and(|fired| now.duration_since(fired) < Duration::from_secs(rule.cooldown_secs));
+    if cooling_down {
+        return None;
+    }
+
+    state.active = true;
+    state.last_fired = Some(now);
+    Some(AlertState::Triggered)
+}
+
+/// Checks the rules' actions, so runaway flashing is rejected when saving.
+pub fn validate(rules: &[AlertRule]) -> Result<(), String> {
+    for rule in rules {
+        for action in rule.actions.iter().chain(&rule.clear_actions) {
+            if let AlertAction::Flash { times, .. } = action {
+                if *times > MAX_FLASHES {
+                    return Err(format!(
+                        "Rule '{}': flash at most {} times, not {}",
+                        rule.name, MAX_FLASHES, times
+                    ));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn run_action(action: &AlertAction, event: &AlertEvent) -> Result<(), String> {
+    match action {
+        AlertAction::Flash {
+            ip_address,
+            lcd_index,
+            color,
+            times,
+        } => {
+            let rgb = <[u8; 3]>::from(*color);
+            for _ in 0..*times {
+                draw_commands::send_solid_color(ip_address, *lcd_index, rgb).await?;
+                tokio::time::sleep(FLASH_INTERVAL).await;
+                draw_commands::send_solid_color(ip_address, *lcd_index, [0, 0, 0]).await?;
+                tokio::time::sleep(FLASH_INTERVAL).await;
+            }
+            draw_commands::send_solid_color(ip_address, *lcd_index, rgb).await
+        }
+        AlertAction::ShowText {
+            ip_address,
+            lcd_index,
+            text,
+            color,
+        } => {
+            let content = text
+                .replace("{value}", &format!("{:.0}", event.value))
+                .replace("{threshold}", &format!("{:.0}", event.threshold))
+                .replace("{metric}", &event.metric);
+
+            // One line across the middle of the panel, whatever its size
+            let resolution = device_commands::resolution(ip_address).await;
+            let width = u8::try_from(resolution).unwrap_or(u8::MAX);
+
+            draw_commands::set_screen_text(
+                ip_address.clone(),
+                *lcd_index,
+                TextConfig {
+                    id: ALERT_TEXT_ID,
+                    content,
+                    x: 0,
+                    y: width.saturating_sub(16) / 2,
+                    font: None,
+                    color: *color,
+                    alignment: Some(TextAlignment::Middle),
+                    text_width: Some(width),
+                    direction: None,
+                    speed: None,
+                },
+            )
+            .await
+        }
+        AlertAction::ShowImage {
+            ip_address,
+            lcd_index,
+            source,
+        } => {
+            let img = draw_commands::load_image(source).await?;
+            draw_commands::send_image(ip_address, &[*lcd_index], img).await
+        }
+        AlertAction::SetBrightness {
+            ip_address,
+            brightness,
+        } => device_commands::set_brightness(ip_address.clone(), *brightness).await,
+        AlertAction::PlayBuzzer {
+            ip_address,
+            pattern,
+        } => device_commands::play_buzzer_pattern(ip_address.clone(), *pattern).await,
+        AlertAction::Notify => Ok(()),
+    }
+}
+
+async fn dispatch(rule: AlertRule, event: AlertEvent) {
+    if let Some(listener) = LISTENER.get() {
+        listener(&event);
+    }
+
+    let actions = match event.state {
+        AlertState::Triggered => &rule.actions,
+        AlertState::Cleared => &rule.clear_actions,
+    };
+
+    for action in actions {
+        if let Err(e) = run_action(action, &event).await {
+            eprintln!("[Alerts] Rule '{}' action failed: {}", rule.id, e);
+        }
+    }
+}
+
+async fn evaluate_rules(rules: Vec<AlertRule>) {
+    let metrics = metric_providers::collect_metrics().await;
+    let target = metric_pipeline::TARGET_ALERTS;
+    let values =
+        metric_pipeline::process(target, &metrics, &metric_pipeline::pipelines_for(target));
+    let now = Instant::now();
+
+    let mut fired = Vec::new();
+    if let Ok(mut states) = RULE_STATES.lock() {
+        states.retain(|id, _| rules.iter().any(|r| &r.id == id));
+
+        for rule in rules {
+            let Some(value) = values.get(&rule.metric).map(|v| v.value) else {
+                continue;
+            };
+
+            let state = states.entry(rule.id.clone()).or_default();
+            if let Some(alert_state) = evaluate(&rule, state, value, now) {
+                let event = AlertEvent {
+                    rule_id: rule.id.clone(),
+                    name: rule.name.clone(),
+                    metric: rule.metric.clone(),
+                    value,
+                    threshold: rule.threshold,
+                    state: alert_state,
+                };
+                fired.push((rule, event));
+            }
+        }
+    }
+
+    for (rule, event) in fired {
+        runtime::spawn(dispatch(rule, event));
+    }
+}
+
+/// Called for every alert event before the rule's actions run, e.g. to notify the frontend.
+pub type AlertListener = Box<dyn Fn(&AlertEvent) + Send + Sync>;
+
+/// Starts the background loop that evaluates alert rules against fresh metrics;
+/// started at most once. Without a `listener` only the rule actions run.
+pub fn start(listener: Option<AlertListener>) {
+    if STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    if let Some(listener) = listener {
+        let _ = LISTENER.set(listener);
+    }
+
+    runtime::spawn(async {
+        loop {
+            let rules: Vec<AlertRule> = app_settings::alert_rules()
+                .into_iter()
+                .filter(|r| r.enabled)
+                .collect();
+
+            if !rules.is_empty() {
+                evaluate_rules(rules).await;
+            }
+
+            tokio::time::sleep(EVALUATION_INTERVAL).await;
+        }
+    });
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn test_alert_rule(rule: AlertRule) -> Result<(), String> {
+    validate(std::slice::from_ref(&rule))?;
+
+    let event = AlertEvent {
+        rule_id: rule.id.clone(),
+        name: rule.name.clone(),
+        metric: rule.metric.clone(),
+        value: rule.threshold,
+        threshold: rule.threshold,
+        state: AlertState::Triggered,
+    };
+
+    for action in &rule.actions {
+        run_action(action, &event).await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(comparison: Comparison) -> AlertRule {
+        AlertRule {
+            id: "hot".to_string(),
+            name: "Hot".to_string(),
+            metric: "cpu_temperature".to_string(),
+            comparison,
+            threshold: 80.0,
+            duration_secs: 0,
+            hysteresis: 5.0,
+            cooldown_secs: 0,
+            enabled: true,
+            actions: Vec::new(),
+            clear_actions: Vec::new(),
+        }
+    }
+
+    fn secs(start: Instant, secs: u64) -> Instant {
+        start + Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn alerts_clear_only_past_the_hysteresis() {
+        let above = rule(Comparison::Above);
+        let mut state = RuleState::default();
+        let start = Instant::now();
+
+        assert!(evaluate(&above, &mut state, 80.0, start).is_none());
+        assert!(matches!(
+            evaluate(&above, &mut state, 81.0, start),
+            Some(AlertState::Triggered)
+        ));
+        // Still breached, then back under the threshold but within the hysteresis
+        assert!(evaluate(&above, &mut state, 90.0, secs(start, 2)).is_none());
+        assert!(evaluate(&above, &mut state, 76.0, secs(start, 4)).is_none());
+        assert!(matches!(
+            evaluate(&above, &mut state, 75.0, secs(start, 6)),
+            Some(AlertState::Cleared)
+        ));
+
+        let below = rule(Comparison::Below);
+        let mut state = RuleState::default();
+        assert!(evaluate(&below, &mut state, 79.0, start).is_some());
+        assert!(evaluate(&below, &mut state, 84.0, secs(start, 2)).is_none());
+        assert!(evaluate(&below, &mut state, 85.0, secs(start, 4)).is_some());
+    }
+
+    #[test]
+    fn alerts_wait_for_the_duration_and_the_cooldown() {
+        let rule = AlertRule {
+            duration_secs: 10,
+            cooldown_secs: 60,
+            ..rule(Comparison::Above)
+        };
+        let mut state = RuleState::default();
+        let start = Instant::now();
+
+        assert!(evaluate(&rule, &mut state, 90.0, start).is_none());
+        assert!(evaluate(&rule, &mut state, 90.0, secs(start, 9)).is_none());
+        assert!(evaluate(&rule, &mut state, 90.0, secs(start, 10)).is_some());
+        assert!(evaluate(&rule, &mut state, 70.0, secs(start, 12)).is_some());
+
+        // Breached long enough again, but within the cooldown of the last firing
+        assert!(evaluate(&rule, &mut state, 90.0, secs(start, 20)).is_none());
+        assert!(evaluate(&rule, &mut state, 90.0, secs(start, 40)).is_none());
+        assert!(evaluate(&rule, &mut state, 90.0, secs(start, 70)).is_some());
+    }
+
+    #[test]
+    fn long_flashes_are_rejected() {
+        let flash = |times| AlertAction::Flash {
+            ip_address: "192.168.1.50".to_string(),
+            lcd_index: 0,
+            color: Color { r: 255, g: 0, b: 0 },
+            times,
+        };
+        let mut rules = vec![AlertRule {
+            actions: vec![flash(MAX_FLASHES)],
+            ..rule(Comparison::Above)
+        }];
+        assert!(validate(&rules).is_ok());
+
+        rules[0].clear_actions.push(flash(MAX_FLASHES + 1));
+        assert!(validate(&rules).is_err());
+    }
+}
@@ -1,52 +1,598 @@
-use std::fs;
-use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::OnceLock;
-
-use serde::{Deserialize, Serialize};
-
-pub static CLOSE_TO_TRAY: AtomicBool = AtomicBool::new(true);
-static SETTINGS_PATH: OnceLock<PathBuf> = OnceLock::new();
-
-#[derive(Serialize, Deserialize)]
-struct PersistedSettings {
-    close_to_tray: bool,
-}
-
-pub fn init(app_data_dir: PathBuf) {
-    let path = app_data_dir.join("settings.json");
-
-    if let Ok(data) = fs::read_to_string(&path) {
-        if let Ok(settings) = serde_json::from_str::<PersistedSettings>(&data) {
-            CLOSE_TO_TRAY.store(settings.close_to_tray, Ordering::Relaxed);
-        }
-    }
-
-    SETTINGS_PATH.set(path).ok();
-}
-
-fn persist() {
-    let Some(path) = SETTINGS_PATH.get() else {
-        return;
-    };
-
-    let settings = PersistedSettings {
-        close_to_tray: CLOSE_TO_TRAY.load(Ordering::Relaxed),
-    };
-
-    if let Some(parent) = path.parent() {
-        let _ = fs::create_dir_all(parent);
-    }
-    let _ = fs::write(path, serde_json::to_string_pretty(&settings).unwrap_or_default());
-}
-
-#[tauri::command]
-pub fn set_close_to_tray(enabled: bool) {
-    CLOSE_TO_TRAY.store(enabled, Ordering::Relaxed);
-    persist();
-}
-
-#[tauri::command]
-pub fn get_close_to_tray() -> bool {
-    CLOSE_TO_TRAY.load(Ordering::Relaxed)
-}
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::alerts::{self, AlertRule};
+use crate::automation::{self, AutomationJob};
+use crate::brightness_schedule::{self, BrightnessSchedule};
+use crate::channels::TrayChannelSettings;
+use crate::groups::{self, DeviceGroup};
+use crate::metric_pipeline::{self, MetricPipeline};
+use crate::metric_providers::{self, ProviderConfig};
+use crate::playlists::Playlist;
+use crate::prometheus_exporter::{self, PrometheusSettings};
+use crate::rest_api::{self, RestApiSettings};
+use crate::time_sync::{self, TimeSyncSettings};
+use crate::weather::{self, WeatherSettings};
+
+pub static CLOSE_TO_TRAY: AtomicBool = AtomicBool::new(true);
+static METRIC_PROVIDERS: Mutex<Vec<ProviderConfig>> = Mutex::new(Vec::new());
+static SENSOR_MAPPING: Mutex<BTreeMap<String, String>> = Mutex::new(BTreeMap::new());
+static METRIC_PIPELINES: Mutex<BTreeMap<String, BTreeMap<String, MetricPipeline>>> =
+    Mutex::new(BTreeMap::new());
+static ALERT_RULES: Mutex<Vec<AlertRule>> = Mutex::new(Vec::new());
+static AUTOMATION_JOBS: Mutex<Vec<AutomationJob>> = Mutex::new(Vec::new());
+static PLAYLISTS: Mutex<Vec<Playlist>> = Mutex::new(Vec::new());
+static DEVICE_GROUPS: Mutex<Vec<DeviceGroup>> = Mutex::new(Vec::new());
+static PROMETHEUS: Mutex<Option<PrometheusSettings>> = Mutex::new(None);
+static REST_API: Mutex<Option<RestApiSettings>> = Mutex::new(None);
+static TIME_SYNC: Mutex<Option<TimeSyncSettings>> = Mutex::new(None);
+static WEATHER: Mutex<Option<WeatherSettings>> = Mutex::new(None);
+static BRIGHTNESS_SCHEDULE: Mutex<Option<BrightnessSchedule>> = Mutex::new(None);
+static TRAY_CHANNEL: Mutex<Option<TrayChannelSettings>> = Mutex::new(None);
+static SETTINGS_PATH: OnceLock<PathBuf> = OnceLock::new();
+// Set when settings.json could not be read and not be backed up either; saving would lose it
+static PERSIST_BLOCKED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Serialize)]
+struct PersistedSettings {
+    close_to_tray: bool,
+    metric_providers: Vec<ProviderConfig>,
+    /// Metric slot (e.g. `cpu_temperature`) -> pinned sensor id.
+    sensor_mapping: BTreeMap<String, String>,
+    /// Output target (`ui`, `device` or a device IP) -> metric -> pipeline.
+    metric_pipelines: BTreeMap<String, BTreeMap<String, MetricPipeline>>,
+    alert_rules: Vec<AlertRule>,
+    prometheus: PrometheusSettings,
+    rest_api: RestApiSettings,
+    time_sync: TimeSyncSettings,
+    weather: WeatherSettings,
+    tray_channel: Option<TrayChannelSettings>,
+    brightness_schedule: BrightnessSchedule,
+    automation_jobs: Vec<AutomationJob>,
+    playlists: Vec<Playlist>,
+    device_groups: Vec<DeviceGroup>,
+}
+
+/// Reads `settings.json` one section at a time, so a section or list entry that no
+/// longer parses is skipped and reported instead of discarding every setting.
+struct SettingsReader {
+    root: serde_json::Map<String, Value>,
+    problems: Vec<String>,
+}
+
+impl SettingsReader {
+    fn section<T: DeserializeOwned + Default>(&mut self, key: &str) -> T {
+        match self.root.remove(key) {
+            None | Some(Value::Null) => T::default(),
+            Some(value) => serde_json::from_value(value).unwrap_or_else(|e| {
+                self.problems.push(format!("{}: {}", key, e));
+                T::default()
+            }),
+        }
+    }
+
+    fn list<T: DeserializeOwned>(&mut self, key: &str) -> Vec<T> {
+        let items = match self.root.remove(key) {
+            None | Some(Value::Null) => return Vec::new(),
+            Some(Value::Array(items)) => items,
+            Some(_) => {
+                self.problems.push(format!("{}: expected a list", key));
+                return Vec::new();
+            }
+        };
+
+        items
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, item)| match serde_json::from_value(item) {
+                Ok(item) => Some(item),
+                Err(e) => {
+                    self.problems.push(format!("{}[{}]: {}", key, i, e));
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+/// Parses `data`, returning the settings that could be read and what was skipped.
+fn read_settings(data: &str) -> Result<(PersistedSettings, Vec<String>), String> {
+    let root = match serde_json::from_str(data) {
+        Ok(Value::Object(root)) => root,
+        Ok(_) => return Err("settings are not a JSON object".to_string()),
+        Err(e) => return Err(e.to_string()),
+    };
+    let mut reader = SettingsReader {
+        root,
+        problems: Vec::new(),
+    };
+
+    let settings = PersistedSettings {
+        close_to_tray: reader
+            .section::<Option<bool>>("close_to_tray")
+            .unwrap_or(true),
+        metric_providers: reader.list("metric_providers"),
+        sensor_mapping: reader.section("sensor_mapping"),
+        metric_pipelines: reader.section("metric_pipelines"),
+        alert_rules: reader.list("alert_rules"),
+        prometheus: reader.section("prometheus"),
+        rest_api: reader.section("rest_api"),
+        time_sync: reader.section("time_sync"),
+        weather: reader.section("weather"),
+        tray_channel: reader.section("tray_channel"),
+        brightness_schedule: reader.section("brightness_schedule"),
+        automation_jobs: reader.list("automation_jobs"),
+        playlists: reader.list("playlists"),
+        device_groups: reader.list("device_groups"),
+    };
+    Ok((settings, reader.problems))
+}
+
+/// Keeps a copy of a settings file that could not be read completely, since the next
+/// save only writes what was understood.
+fn back_up(path: &Path) -> bool {
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let backup = path.with_extension(format!("json.{}.bak", stamp));
+
+    match fs::copy(path, &backup) {
+        Ok(_) => {
+            eprintln!(
+                "[Settings] Kept the previous settings in {}",
+                backup.display()
+            );
+            true
+        }
+        Err(e) => {
+            eprintln!("[Settings] Failed to back up {}: {}", path.display(), e);
+            false
+        }
+    }
+}
+
+pub fn init(app_data_dir: PathBuf) {
+    let path = app_data_dir.join("settings.json");
+
+    if let Ok(data) = fs::read_to_string(&path) {
+        match read_settings(&data) {
+            Ok((settings, problems)) => {
+                if !problems.is_empty() {
+                    for problem in &problems {
+                        eprintln!("[Settings] Skipped {}", problem);
+                    }
+                    if !back_up(&path) {
+                        PERSIST_BLOCKED.store(true, Ordering::Relaxed);
+                    }
+                }
+                load(settings);
+            }
+            Err(e) => {
+                eprintln!("[Settings] Failed to read {}: {}", path.display(), e);
+                if !back_up(&path) {
+                    PERSIST_BLOCKED.store(true, Ordering::Relaxed);
+                }
+            }
+        }
+    }
+
+    SETTINGS_PATH.set(path).ok();
+}
+
+fn load(settings: PersistedSettings) {
+    CLOSE_TO_TRAY.store(settings.close_to_tray, Ordering::Relaxed);
+    if let Ok(mut providers) = METRIC_PROVIDERS.lock() {
+        *providers = settings.metric_providers;
+    }
+    if let Ok(mut mapping) = SENSOR_MAPPING.lock() {
+        *mapping = settings.sensor_mapping;
+    }
+    if let Ok(mut pipelines) = METRIC_PIPELINES.lock() {
+        *pipelines = settings.metric_pipelines;
+    }
+    if let Ok(mut rules) = ALERT_RULES.lock() {
+        *rules = settings.alert_rules;
+    }
+    if let Ok(mut prometheus) = PROMETHEUS.lock() {
+        *prometheus = Some(settings.prometheus);
+    }
+    if let Ok(mut api) = REST_API.lock() {
+        *api = Some(settings.rest_api);
+    }
+    if let Ok(mut time_sync) = TIME_SYNC.lock() {
+        *time_sync = Some(settings.time_sync);
+    }
+    if let Ok(mut weather) = WEATHER.lock() {
+        *weather = Some(settings.weather);
+    }
+    if let Ok(mut tray_channel) = TRAY_CHANNEL.lock() {
+        *tray_channel = settings.tray_channel;
+    }
+    if let Ok(mut schedule) = BRIGHTNESS_SCHEDULE.lock() {
+        *schedule = Some(settings.brightness_schedule);
+    }
+    if let Ok(mut jobs) = AUTOMATION_JOBS.lock() {
+        *jobs = settings.automation_jobs;
+    }
+    if let Ok(mut playlists) = PLAYLISTS.lock() {
+        *playlists = settings.playlists;
+    }
+    if let Ok(mut groups) = DEVICE_GROUPS.lock() {
+        *groups = settings.device_groups;
+    }
+}
+
+pub fn metric_providers() -> Vec<ProviderConfig> {
+    METRIC_PROVIDERS
+        .lock()
+        .map(|p| p.clone())
+        .unwrap_or_default()
+}
+
+pub fn sensor_mapping() -> BTreeMap<String, String> {
+    SENSOR_MAPPING.lock().map(|m| m.clone()).unwrap_or_default()
+}
+
+pub fn metric_pipelines() -> BTreeMap<String, BTreeMap<String, MetricPipeline>> {
+    METRIC_PIPELINES
+        .lock()
+        .map(|p| p.clone())
+        .unwrap_or_default()
+}
+
+pub fn alert_rules() -> Vec<AlertRule> {
+    ALERT_RULES.lock().map(|r| r.clone()).unwrap_or_default()
+}
+
+pub fn prometheus_settings() -> PrometheusSettings {
+    PROMETHEUS
+        .lock()
+        .ok()
+        .and_then(|p| p.clone())
+        .unwrap_or_default()
+}
+
+pub fn rest_api_settings() -> RestApiSettings {
+    REST_API
+        .lock()
+        .ok()
+        .and_then(|a| a.clone())
+        .unwrap_or_default()
+}
+
+pub fn time_sync_settings() -> TimeSyncSettings {
+    TIME_SYNC
+        .lock()
+        .ok()
+        .and_then(|t| t.clone())
+        .unwrap_or_default()
+}
+
+pub fn weather_settings() -> WeatherSettings {
+    WEATHER
+        .lock()
+        .ok()
+        .and_then(|w| w.clone())
+        .unwrap_or_default()
+}
+
+pub fn tray_channel_settings() -> Option<TrayChannelSettings> {
+    TRAY_CHANNEL.lock().ok().and_then(|t| t.clone())
+}
+
+pub fn brightness_schedule() -> BrightnessSchedule {
+    BRIGHTNESS_SCHEDULE
+        .lock()
+        .ok()
+        .and_then(|s| s.clone())
+        .unwrap_or_default()
+}
+
+pub fn automation_jobs() -> Vec<AutomationJob> {
+    AUTOMATION_JOBS
+        .lock()
+        .map(|j| j.clone())
+        .unwrap_or_default()
+}
+
+pub fn playlists() -> Vec<Playlist> {
+    PLAYLISTS.lock().map(|p| p.clone()).unwrap_or_default()
+}
+
+pub fn device_groups() -> Vec<DeviceGroup> {
+    DEVICE_GROUPS.lock().map(|g| g.clone()).unwrap_or_default()
+}
+
+/// Folder holding `settings.json`, for data kept in files of its own.
+pub fn data_dir() -> Option<PathBuf> {
+    SETTINGS_PATH.get()?.parent().map(PathBuf::from)
+}
+
+fn persist() {
+    let Some(path) = SETTINGS_PATH.get() else {
+        return;
+    };
+    if PERSIST_BLOCKED.load(Ordering::Relaxed) {
+        eprintln!("[Settings] Not saving, the unreadable settings file could not be backed up");
+        return;
+    }
+
+    let settings = PersistedSettings {
+        close_to_tray: CLOSE_TO_TRAY.load(Ordering::Relaxed),
+        metric_providers: metric_providers(),
+        sensor_mapping: sensor_mapping(),
+        metric_pipelines: metric_pipelines(),
+        alert_rules: alert_rules(),
+        prometheus: prometheus_settings(),
+        rest_api: rest_api_settings(),
+        time_sync: time_sync_settings(),
+        weather: weather_settings(),
+        tray_channel: tray_channel_settings(),
+        brightness_schedule: brightness_schedule(),
+        automation_jobs: automation_jobs(),
+        playlists: playlists(),
+        device_groups: device_groups(),
+    };
+
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let _ = fs::write(path, serde_json::to_string_pretty(&settings).unwrap_or_default());
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn set_close_to_tray(enabled: bool) {
+    CLOSE_TO_TRAY.store(enabled, Ordering::Relaxed);
+    persist();
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_close_to_tray() -> bool {
+    CLOSE_TO_TRAY.load(Ordering::Relaxed)
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn set_metric_providers(providers: Vec<ProviderConfig>) -> Result<(), String> {
+    metric_providers::validate(&providers)?;
+    if let Ok(mut guard) = METRIC_PROVIDERS.lock() {
+        *guard = providers;
+    }
+    persist();
+    Ok(())
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_metric_providers() -> Vec<ProviderConfig> {
+    metric_providers()
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn set_sensor_mapping(mapping: BTreeMap<String, String>) {
+    if let Ok(mut guard) = SENSOR_MAPPING.lock() {
+        *guard = mapping;
+    }
+    persist();
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_sensor_mapping() -> BTreeMap<String, String> {
+    sensor_mapping()
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn set_metric_pipelines(pipelines: BTreeMap<String, BTreeMap<String, MetricPipeline>>) {
+    if let Ok(mut guard) = METRIC_PIPELINES.lock() {
+        *guard = pipelines;
+    }
+    metric_pipeline::reset_smoothing();
+    persist();
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_metric_pipelines() -> BTreeMap<String, BTreeMap<String, MetricPipeline>> {
+    metric_pipelines()
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn set_alert_rules(rules: Vec<AlertRule>) -> Result<(), String> {
+    alerts::validate(&rules)?;
+    if let Ok(mut guard) = ALERT_RULES.lock() {
+        *guard = rules;
+    }
+    persist();
+    Ok(())
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_alert_rules() -> Vec<AlertRule> {
+    alert_rules()
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn set_prometheus_settings(settings: PrometheusSettings) -> Result<(), String> {
+    prometheus_exporter::apply(settings.clone()).await?;
+    if let Ok(mut guard) = PROMETHEUS.lock() {
+        *guard = Some(settings);
+    }
+    persist();
+    Ok(())
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_prometheus_settings() -> PrometheusSettings {
+    prometheus_settings()
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn set_rest_api_settings(settings: RestApiSettings) -> Result<(), String> {
+    rest_api::apply(settings.clone()).await?;
+    if let Ok(mut guard) = REST_API.lock() {
+        *guard = Some(settings);
+    }
+    persist();
+    Ok(())
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_rest_api_settings() -> RestApiSettings {
+    rest_api_settings()
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn set_time_sync_settings(settings: TimeSyncSettings) {
+    if let Ok(mut guard) = TIME_SYNC.lock() {
+        *guard = Some(settings);
+    }
+    persist();
+    time_sync::request_sync();
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_time_sync_settings() -> TimeSyncSettings {
+    time_sync_settings()
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn set_weather_settings(settings: WeatherSettings) {
+    weather::apply_location(&settings);
+    if let Ok(mut guard) = WEATHER.lock() {
+        *guard = Some(settings);
+    }
+    persist();
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_weather_settings() -> WeatherSettings {
+    weather_settings()
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn set_tray_channel_settings(settings: Option<TrayChannelSettings>) {
+    if let Ok(mut guard) = TRAY_CHANNEL.lock() {
+        *guard = settings;
+    }
+    persist();
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_tray_channel_settings() -> Option<TrayChannelSettings> {
+    tray_channel_settings()
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn set_brightness_schedule(schedule: BrightnessSchedule) -> Result<(), String> {
+    brightness_schedule::validate(&schedule)?;
+    if let Ok(mut guard) = BRIGHTNESS_SCHEDULE.lock() {
+        *guard = Some(schedule);
+    }
+    persist();
+    brightness_schedule::request_apply();
+    Ok(())
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_brightness_schedule() -> BrightnessSchedule {
+    brightness_schedule()
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn set_automation_jobs(jobs: Vec<AutomationJob>) -> Result<(), String> {
+    automation::validate(&jobs)?;
+    if let Ok(mut guard) = AUTOMATION_JOBS.lock() {
+        *guard = jobs;
+    }
+    persist();
+    Ok(())
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_automation_jobs() -> Vec<AutomationJob> {
+    automation_jobs()
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn set_playlists(playlists: Vec<Playlist>) {
+    if let Ok(mut guard) = PLAYLISTS.lock() {
+        *guard = playlists;
+    }
+    persist();
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_playlists() -> Vec<Playlist> {
+    playlists()
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn set_device_groups(groups: Vec<DeviceGroup>) -> Result<(), String> {
+    groups::validate(&groups)?;
+    if let Ok(mut guard) = DEVICE_GROUPS.lock() {
+        *guard = groups;
+    }
+    persist();
+    Ok(())
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_device_groups() -> Vec<DeviceGroup> {
+    device_groups()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unreadable_entries_are_skipped_without_losing_other_sections() {
+        let data = r#"{
+            "close_to_tray": false,
+            "sensor_mapping": {"cpu_temperature": "/amdcpu/0/temperature/2"},
+            "alert_rules": [{"id": "broken"}],
+            "rest_api": {"enabled": "yes"},
+            "device_groups": [
+                {"id": "office", "name": "Office", "devices": ["192.168.1.50"]},
+                {"name": "no id"}
+            ],
+            "unknown_section": 1
+        }"#;
+
+        let (settings, problems) = read_settings(data).unwrap();
+
+        assert!(!settings.close_to_tray);
+        assert_eq!(settings.sensor_mapping.len(), 1);
+        assert!(settings.alert_rules.is_empty());
+        assert_eq!(settings.rest_api, RestApiSettings::default());
+        assert_eq!(settings.device_groups.len(), 1);
+        assert_eq!(settings.device_groups[0].id, "office");
+
+        assert_eq!(problems.len(), 3, "{:?}", problems);
+        assert!(problems[0].starts_with("alert_rules[0]:"));
+        assert!(problems[1].starts_with("rest_api:"));
+        assert!(problems[2].starts_with("device_groups[1]:"));
+    }
+
+    #[test]
+    fn missing_sections_fall_back_to_defaults() {
+        let (settings, problems) = read_settings("{}").unwrap();
+
+        assert!(settings.close_to_tray);
+        assert!(settings.tray_channel.is_none());
+        assert!(settings.playlists.is_empty());
+        assert!(problems.is_empty());
+    }
+
+    #[test]
+    fn invalid_json_is_an_error() {
+        assert!(read_settings("{\"close_to_tray\": tru").is_err());
+        assert!(read_settings("[]").is_err());
+    }
+}
@@ -0,0 +1,492 @@
+use std::collections::VecDeque;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Datelike, Local, Timelike};
+use divoom::PC_MONITOR_CLOCK_ID;
+use serde::{Deserialize, Serialize};
+
+use crate::app_settings;
+use crate::channels;
+use crate::device_commands;
+use crate::draw_commands;
+use crate::models::{BuzzerPattern, TextConfig};
+use crate::runtime;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+const MAX_HISTORY: usize = 200;
+
+// Loaded from `automation_history.json` on first use, so runs survive a restart
+static RUN_HISTORY: Mutex<Option<VecDeque<JobRun>>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum JobAction {
+    SetBrightness {
+        ip_address: String,
+        brightness: u8,
+    },
+    SetScreen {
+        ip_address: String,
+        on: bool,
+    },
+    /// `source` is an http(s) URL or a local file path.
+    UploadImage {
+        ip_address: String,
+        lcd_index: u32,
+        source: String,
+    },
+    ShowText {
+        ip_address: String,
+        lcd_index: u32,
+        text_config: TextConfig,
+    },
+    ActivatePcMonitor {
+        ip_address: String,
+        lcd_index: u32,
+    },
+    SelectClock {
+        ip_address: String,
+        lcd_index: u32,
+        clock_id: u64,
+    },
+    PushSystemMetrics {
+        ip_address: String,
+        lcd_index: u32,
+    },
+    PlayBuzzer {
+        ip_address: String,
+        pattern: BuzzerPattern,
+    },
+    Reboot {
+        ip_address: String,
+    },
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutomationJob {
+    pub id: String,
+    pub name: String,
+    /// Five-field cron expression in local time, e.g. `0 9 * * 1-5` or `*/15 * * * *`.
+    pub schedule: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub actions: Vec<JobAction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobRun {
+    pub job_id: String,
+    /// Unix time the run started.
+    pub started_at: i64,
+    pub duration_ms: u64,
+    /// Errors of the failed actions; empty when the run succeeded.
+    pub errors: Vec<String>,
+}
+
+/// Minute, hour, day of month, month and day of week, each as a bit set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Cron matches either day field when both are restricted.
+    any_day: bool,
+    any_weekday: bool,
+}
+
+fn parse_cron_field(field: &str, min: u32, max: u32) -> Result<u64, String> {
+    let mut bits = 0u64;
+
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (
+                range,
+                step.parse::<u32>()
+                    .ok()
+                    .filter(|s| *s > 0)
+                    .ok_or_else(|| format!("Invalid step in '{}'", part))?,
+            ),
+            None => (part, 1),
+        };
+
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => {
+                let parse = |s: &str| {
+                    s.parse::<u32>()
+                        .ok()
+                        .filter(|v| (min..=max).contains(v))
+                        .ok_or_else(|| format!("'{}' is not in {}-{}", s, min, max))
+                };
+                match range.split_once('-') {
+                    Some((a, b)) => (parse(a)?, parse(b)?),
+                    None if step > 1 => (parse(range)?, max),
+                    None => (parse(range)?, parse(range)?),
+                }
+            }
+        };
+
+        if start > end {
+            return Err(format!("Invalid range '{}'", range));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+
+    Ok(bits)
+}
+
+/// Whether `bits` has every value from `min` to `max`, like `*` does.
+fn covers(bits: u64, min: u32, max: u32) -> bool {
+    (min..=max).all(|value| bits & (1 << value) != 0)
+}
+
+impl FromStr for CronSchedule {
+    type Err = String;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields.as_slice() else {
+            return Err(format!(
+                "Expected 5 fields (minute hour day month weekday), got '{}'",
+                expression
+            ));
+        };
+
+        // Both 0 and 7 mean Sunday
+        let mut weekdays = parse_cron_field(weekday, 0, 7)?;
+        if weekdays & (1 << 7) != 0 {
+            weekdays |= 1;
+        }
+
+        let days = parse_cron_field(day, 1, 31)?;
+
+        Ok(Self {
+            minutes: parse_cron_field(minute, 0, 59)?,
+            hours: parse_cron_field(hour, 0, 23)?,
+            days,
+            months: parse_cron_field(month, 1, 12)?,
+            weekdays,
+            // `*/1`, `1-31` or `0-6` restrict nothing either
+            any_day: covers(days, 1, 31),
+            any_weekday: covers(weekdays, 0, 6),
+        })
+    }
+}
+
+impl CronSchedule {
+    pub fn matches(&self, time: &DateTime<Local>) -> bool {
+        let has = |bits: u64, value: u32| bits & (1 << value) != 0;
+
+        let day = has(self.days, time.day());
+        let weekday = has(self.weekdays, time.weekday().num_days_from_sunday());
+        let day_matches = match (self.any_day, self.any_weekday) {
+            (false, false) => day || weekday,
+            _ => day && weekday,
+        };
+
+        has(self.minutes, time.minute())
+            && has(self.hours, time.hour())
+            && has(self.months, time.month())
+            && day_matches
+    }
+}
+
+/// Checks every job's schedule, so broken expressions are rejected when saving.
+pub fn validate(jobs: &[AutomationJob]) -> Result<(), String> {
+    for job in jobs {
+        job.schedule
+            .parse::<CronSchedule>()
+            .map_err(|e| format!("Job '{}': {}", job.name, e))?;
+    }
+    Ok(())
+}
+
+async fn run_action(action: &JobAction) -> Result<(), String> {
+    match action {
+        JobAction::SetBrightness {
+            ip_address,
+            brightness,
+        } => device_commands::set_brightness(ip_address.clone(), *brightness).await,
+        JobAction::SetScreen { ip_address, on } => {
+            device_commands::set_switch_screen(ip_address.clone(), u8::from(*on)).await
+        }
+        JobAction::UploadImage {
+            ip_address,
+            lcd_index,
+            source,
+        } => {
+            let img = draw_commands::load_image(source).await?;
+            draw_commands::send_image(ip_address, &[*lcd_index], img).await
+        }
+        JobAction::ShowText {
+            ip_address,
+            lcd_index,
+            text_config,
+        } => {
+            draw_commands::set_screen_text(ip_address.clone(), *lcd_index, text_config.clone())
+                .await
+        }
+        JobAction::ActivatePcMonitor {
+            ip_address,
+            lcd_index,
+        } => channels::select_clock_face(ip_address.clone(), *lcd_index, PC_MONITOR_CLOCK_ID).await,
+        JobAction::SelectClock {
+            ip_address,
+            lcd_index,
+            clock_id,
+        } => channels::select_clock_face(ip_address.clone(), *lcd_index, *clock_id).await,
+        JobAction::PushSystemMetrics {
+            ip_address,
+            lcd_index,
+        } => draw_commands::send_system_metrics(ip_address.clone(), *lcd_index).await,
+        JobAction::PlayBuzzer {
+            ip_address,
+            pattern,
+        } => device_commands::play_buzzer_pattern(ip_address.clone(), *pattern).await,
+        JobAction::Reboot { ip_address } => {
+            device_commands::reboot_device(ip_address.clone()).await
+        }
+    }
+}
+
+async fn run_job(job: &AutomationJob) -> JobRun {
+    let started_at = Local::now().timestamp();
+    let started = Instant::now();
+
+    let mut errors = Vec::new();
+    for action in &job.actions {
+        if let Err(e) = run_action(action).await {
+            eprintln!("[Automation] Job '{}' action failed: {}", job.id, e);
+            errors.push(e);
+        }
+    }
+
+    let run = JobRun {
+        job_id: job.id.clone(),
+        started_at,
+        duration_ms: started.elapsed().as_millis() as u64,
+        errors,
+    };
+
+    if let Ok(mut guard) = RUN_HISTORY.lock() {
+        let history = guard.get_or_insert_with(load_history);
+        if history.len() >= MAX_HISTORY {
+            history.pop_front();
+        }
+        history.push_back(run.clone());
+        save_history(history);
+    }
+    run
+}
+
+fn history_path() -> Option<PathBuf> {
+    app_settings::data_dir().map(|dir| dir.join("automation_history.json"))
+}
+
+fn load_history() -> VecDeque<JobRun> {
+    history_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+fn save_history(history: &VecDeque<JobRun>) {
+    let Some(path) = history_path() else {
+        return;
+    };
+    let result = serde_json::to_string(history)
+        .map_err(|e| e.to_string())
+        .and_then(|data| fs::write(&path, data).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        eprintln!("[Automation] Failed to save run history: {}", e);
+    }
+}
+
+/// Starts the background job that runs automation jobs when their schedule matches.
+pub fn start() {
+    runtime::spawn(async {
+        let mut last_minute = None;
+
+        loop {
+            let now = Local::now();
+            let minute = now.timestamp() / 60;
+
+            if last_minute != Some(minute) {
+                last_minute = Some(minute);
+
+                for job in app_settings::automation_jobs() {
+                    let due = job.enabled
+                        && job
+                            .schedule
+                            .parse::<CronSchedule>()
+                            .is_ok_and(|schedule| schedule.matches(&now));
+                    if due {
+                        runtime::spawn(async move {
+                            run_job(&job).await;
+                        });
+                    }
+                }
+            }
+
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn run_automation_job(job_id: String) -> Result<JobRun, String> {
+    let job = app_settings::automation_jobs()
+        .into_iter()
+        .find(|job| job.id == job_id)
+        .ok_or_else(|| format!("Job '{}' not found", job_id))?;
+
+    Ok(run_job(&job).await)
+}
+
+/// Recent runs, newest first, optionally of one job only.
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_automation_history(job_id: Option<String>) -> Vec<JobRun> {
+    RUN_HISTORY
+        .lock()
+        .map(|mut guard| {
+            guard
+                .get_or_insert_with(load_history)
+                .iter()
+                .rev()
+                .filter(|run| job_id.as_ref().is_none_or(|id| &run.job_id == id))
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        // June 2024; the 13th is a Thursday, the 16th a Sunday
+        Local
+            .with_ymd_and_hms(2024, 6, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    fn schedule(expression: &str) -> CronSchedule {
+        expression.parse().unwrap()
+    }
+
+    #[test]
+    fn steps_count_from_the_start_of_the_range() {
+        let every_quarter = schedule("*/15 * * * *");
+        for minute in [0, 15, 30, 45] {
+            assert!(every_quarter.matches(&at(13, 10, minute)), "{}", minute);
+        }
+        assert!(!every_quarter.matches(&at(13, 10, 10)));
+
+        let from_five = schedule("5/20 * * * *");
+        assert!(from_five.matches(&at(13, 10, 25)));
+        assert!(from_five.matches(&at(13, 10, 45)));
+        assert!(!from_five.matches(&at(13, 10, 0)));
+
+        let even_hours = schedule("0 8-18/2 * * *");
+        assert!(even_hours.matches(&at(13, 10, 0)));
+        assert!(!even_hours.matches(&at(13, 11, 0)));
+        assert!(!even_hours.matches(&at(13, 20, 0)));
+    }
+
+    #[test]
+    fn seven_and_zero_both_mean_sunday() {
+        for expression in ["0 9 * * 7", "0 9 * * 0", "0 9 * * 5-7"] {
+            assert!(
+                schedule(expression).matches(&at(16, 9, 0)),
+                "{}",
+                expression
+            );
+        }
+        assert!(!schedule("0 9 * * 7").matches(&at(15, 9, 0)));
+        assert!(!schedule("0 9 * * 1-5").matches(&at(16, 9, 0)));
+    }
+
+    #[test]
+    fn restricted_day_fields_match_either_one() {
+        // The 13th, or any Friday
+        let both = schedule("0 9 13 * 5");
+        assert!(both.matches(&at(13, 9, 0)));
+        assert!(both.matches(&at(14, 9, 0)));
+        assert!(!both.matches(&at(12, 9, 0)));
+
+        // With one field unrestricted, the other one alone decides
+        assert!(schedule("0 9 13 * *").matches(&at(13, 9, 0)));
+        assert!(!schedule("0 9 13 * *").matches(&at(14, 9, 0)));
+        assert!(schedule("0 9 * * 5").matches(&at(14, 9, 0)));
+        assert!(!schedule("0 9 * * 5").matches(&at(13, 9, 0)));
+    }
+
+    #[test]
+    fn full_ranges_count_as_unrestricted() {
+        // Friday the 14th only, as with `0 9 * * 5`
+        for expression in ["0 9 */1 * 5", "0 9 1-31 * 5", "0 9 1-15,16-31 * 5"] {
+            assert!(
+                schedule(expression).matches(&at(14, 9, 0)),
+                "{}",
+                expression
+            );
+            assert!(
+                !schedule(expression).matches(&at(13, 9, 0)),
+                "{}",
+                expression
+            );
+        }
+        // The 13th only, as with `0 9 13 * *`
+        for expression in ["0 9 13 * 0-6", "0 9 13 * 1-7", "0 9 13 * */1"] {
+            assert!(
+                schedule(expression).matches(&at(13, 9, 0)),
+                "{}",
+                expression
+            );
+            assert!(
+                !schedule(expression).matches(&at(14, 9, 0)),
+                "{}",
+                expression
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_expressions_are_rejected() {
+        for expression in [
+            "",
+            "* * * *",
+            "* * * * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "* * * 13 *",
+            "* * * * 8",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "a * * * *",
+        ] {
+            assert!(
+                expression.parse::<CronSchedule>().is_err(),
+                "{}",
+                expression
+            );
+        }
+    }
+}
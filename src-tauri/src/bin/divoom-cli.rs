@@ -0,0 +1,490 @@
+//! Headless command-line client for Divoom devices.
+//!
+//! Shares the device code with the desktop app, so it behaves the same but runs
+//! without a window, e.g. on a server or from a cron job:
+//!
+//! ```text
+//! divoom-cli scan
+//! divoom-cli brightness 192.168.1.50 60
+//! divoom-cli pc-monitor 192.168.1.50 --lcd 2 --activate
+//! ```
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Duration;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+
+use divoom::{BuzzerPattern, StopwatchAction, PC_MONITOR_CLOCK_ID};
+use divoom_monitor_lib::models::{
+    Color, Font, ScrollDirection, ScrollSpeed, TextAlignment, TextConfig,
+};
+use divoom_monitor_lib::{
+    alerts, app_settings, channels, device_commands, divoom_api, draw_commands, groups, pic_ids,
+    scenes, system_metrics, time_sync, weather,
+};
+
+#[derive(Parser)]
+#[command(
+    name = "divoom-cli",
+    version,
+    about = "Control Divoom devices without the desktop app"
+)]
+struct Cli {
+    /// Directory with the desktop app's `settings.json`, to reuse its metric
+    /// providers, sensor mapping, pipelines, alert rules and scenes.
+    #[arg(long, global = true)]
+    settings_dir: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Power {
+    On,
+    Off,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ChannelArg {
+    Faces,
+    Cloud,
+    Visualizer,
+    Custom,
+    BlackScreen,
+}
+
+impl From<ChannelArg> for divoom::Channel {
+    fn from(value: ChannelArg) -> Self {
+        match value {
+            ChannelArg::Faces => divoom::Channel::Faces,
+            ChannelArg::Cloud => divoom::Channel::Cloud,
+            ChannelArg::Visualizer => divoom::Channel::Visualizer,
+            ChannelArg::Custom => divoom::Channel::Custom,
+            ChannelArg::BlackScreen => divoom::Channel::BlackScreen,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Stopwatch {
+    Start,
+    Stop,
+    Reset,
+}
+
+impl From<Stopwatch> for StopwatchAction {
+    fn from(value: Stopwatch) -> Self {
+        match value {
+            Stopwatch::Start => StopwatchAction::Start,
+            Stopwatch::Stop => StopwatchAction::Stop,
+            Stopwatch::Reset => StopwatchAction::Reset,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Beep {
+    Short,
+    Triple,
+    Alarm,
+}
+
+impl From<Beep> for BuzzerPattern {
+    fn from(value: Beep) -> Self {
+        match value {
+            Beep::Short => BuzzerPattern::ShortBeep,
+            Beep::Triple => BuzzerPattern::TripleBeep,
+            Beep::Alarm => BuzzerPattern::Alarm,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum GroupCommand {
+    /// Set brightness (0-100).
+    Brightness {
+        #[arg(value_parser = clap::value_parser!(u8).range(0..=100))]
+        value: u8,
+    },
+    /// Turn the screens on or off.
+    Screen { power: Power },
+    /// Show an image from a local file or an http(s) URL.
+    Upload {
+        source: String,
+        /// Target screens, e.g. `1,3,4`.
+        #[arg(long, default_value = "0", value_delimiter = ',')]
+        screen: Vec<u32>,
+    },
+    /// Push system metrics to the PC Monitor clock once.
+    PcMonitor {
+        #[arg(long, default_value_t = 0)]
+        lcd: u32,
+    },
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List devices found on the local network.
+    Scan,
+    /// Print the device configuration.
+    Info { ip: String },
+    /// Set brightness (0-100).
+    Brightness {
+        ip: String,
+        #[arg(value_parser = clap::value_parser!(u8).range(0..=100))]
+        value: u8,
+    },
+    /// Turn the screen on or off.
+    Screen { ip: String, power: Power },
+    /// Show an image from a local file or an http(s) URL.
+    Upload {
+        ip: String,
+        source: String,
+        /// Target screens, e.g. `1,3,4`.
+        #[arg(long, default_value = "0", value_delimiter = ',')]
+        screen: Vec<u32>,
+    },
+    /// Spread an image or GIF across all five Times Gate LCDs.
+    Panorama {
+        ip: String,
+        source: String,
+        /// Gap between two LCDs in pixels, skipped so lines stay straight.
+        #[arg(long, default_value_t = 0)]
+        bezel: u32,
+    },
+    /// Show a text element.
+    Text {
+        ip: String,
+        content: String,
+        #[arg(long, default_value_t = 0)]
+        screen: u32,
+        /// Text element id (0-18, 19 and 20 are the app's temperature and alert
+        /// texts); elements with other ids are kept.
+        #[arg(long, default_value_t = 0, value_parser = clap::value_parser!(u8).range(0..=18))]
+        id: u8,
+        #[arg(long, default_value_t = 0)]
+        x: u8,
+        #[arg(long, default_value_t = 0)]
+        y: u8,
+        /// arial, times_new_roman, verdana, courier_new, georgia, garamond,
+        /// comic_sans_ms or impact (default).
+        #[arg(long)]
+        font: Option<Font>,
+        /// `#RRGGBB`, `#RGB`, `r,g,b` or a CSS color name.
+        #[arg(long)]
+        color: Option<Color>,
+        /// scroll (default), normal, middle, right or left.
+        #[arg(long)]
+        alignment: Option<TextAlignment>,
+        #[arg(long)]
+        width: Option<u8>,
+        /// Scroll direction: left (default) or right.
+        #[arg(long)]
+        direction: Option<ScrollDirection>,
+        /// Scroll speed: slow, normal (default) or fast.
+        #[arg(long)]
+        speed: Option<ScrollSpeed>,
+    },
+    /// Remove every text element from a screen.
+    ClearText {
+        ip: String,
+        #[arg(long, default_value_t = 0)]
+        screen: u32,
+    },
+    /// Keep pushing system metrics to the PC Monitor clock until interrupted.
+    PcMonitor {
+        ip: String,
+        #[arg(long, default_value_t = 0)]
+        lcd: u32,
+        /// Seconds between pushes.
+        #[arg(long, default_value_t = 2)]
+        interval: u64,
+        /// Switch the screen to the PC Monitor clock first.
+        #[arg(long)]
+        activate: bool,
+        /// Start the LibreHardwareMonitor sidecar for CPU and GPU temperatures
+        /// (Windows; asks for administrator rights).
+        #[arg(long)]
+        sidecar: bool,
+    },
+    /// Switch channel, or print the current one when no channel is given.
+    Channel {
+        ip: String,
+        #[arg(value_name = "CHANNEL")]
+        channel: Option<ChannelArg>,
+    },
+    /// Show a clock face on one LCD; see `clock-faces` for ids.
+    Clock {
+        ip: String,
+        clock_id: u64,
+        #[arg(long, default_value_t = 0)]
+        lcd: u32,
+    },
+    /// List clock faces of a category, or the categories when none is given.
+    ClockFaces {
+        category: Option<String>,
+        #[arg(long, default_value_t = 1)]
+        page: u32,
+    },
+    /// Print the weather the device currently shows.
+    Weather { ip: String },
+    /// Set the location the device fetches its weather for.
+    Location {
+        ip: String,
+        #[arg(allow_negative_numbers = true)]
+        longitude: f64,
+        #[arg(allow_negative_numbers = true)]
+        latitude: f64,
+    },
+    /// Set the device time zone and clock from this machine.
+    SyncTime { ip: String },
+    /// Start a countdown, or stop it with `--stop`.
+    Timer {
+        ip: String,
+        #[arg(default_value_t = 0, value_parser = clap::value_parser!(u32).range(0..=99))]
+        minutes: u32,
+        #[arg(default_value_t = 0, value_parser = clap::value_parser!(u32).range(0..=59))]
+        seconds: u32,
+        #[arg(long)]
+        stop: bool,
+    },
+    /// Start, stop or reset the stopwatch.
+    Stopwatch { ip: String, action: Stopwatch },
+    /// Show the scoreboard (0-999 per team).
+    Score {
+        ip: String,
+        #[arg(value_parser = clap::value_parser!(u16).range(0..=999))]
+        red: u16,
+        #[arg(value_parser = clap::value_parser!(u16).range(0..=999))]
+        blue: u16,
+    },
+    /// Turn the noise meter on or off.
+    Noise { ip: String, power: Power },
+    /// Apply a scene saved in the desktop app; needs `--settings-dir`.
+    Scene { ip: String, name: String },
+    /// List the scenes saved in the desktop app; needs `--settings-dir`.
+    Scenes,
+    /// Run a command on every device of a group saved in the desktop app; needs
+    /// `--settings-dir`.
+    Group {
+        group_id: String,
+        #[command(subcommand)]
+        command: GroupCommand,
+    },
+    /// Play a buzzer pattern.
+    Beep {
+        ip: String,
+        #[arg(default_value = "short")]
+        pattern: Beep,
+    },
+    /// Start the device's picture id sequence over, e.g. when uploads keep
+    /// showing an old picture.
+    ResetPicId { ip: String },
+    /// Reboot the device.
+    Reboot { ip: String },
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Failed to serialize output: {}", e))?;
+    println!("{}", json);
+    Ok(())
+}
+
+async fn run_group(group_id: String, command: GroupCommand) -> Result<(), String> {
+    let results = match command {
+        GroupCommand::Brightness { value } => groups::group_set_brightness(group_id, value).await?,
+        GroupCommand::Screen { power } => {
+            groups::group_set_screen(group_id, matches!(power, Power::On)).await?
+        }
+        GroupCommand::Upload { source, screen } => {
+            groups::group_upload_image(group_id, screen, source).await?
+        }
+        GroupCommand::PcMonitor { lcd } => groups::group_send_system_metrics(group_id, lcd).await?,
+    };
+    print_json(&results)?;
+
+    let failed = results.iter().filter(|r| r.error.is_some()).count();
+    if failed > 0 {
+        return Err(format!("{} of {} devices failed", failed, results.len()));
+    }
+    Ok(())
+}
+
+async fn run_pc_monitor(
+    ip: String,
+    lcd: u32,
+    interval: u64,
+    activate: bool,
+    sidecar: bool,
+) -> Result<(), String> {
+    if activate {
+        channels::select_clock_face(ip.clone(), lcd, PC_MONITOR_CLOCK_ID).await?;
+    }
+
+    if sidecar {
+        system_metrics::setup_sidecar_service();
+    }
+    alerts::start(None);
+
+    let mut ticker = tokio::time::interval(Duration::from_secs(interval.max(1)));
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {
+                if let Err(e) = draw_commands::send_system_metrics(ip.clone(), lcd).await {
+                    eprintln!("[PC Monitor] {}", e);
+                }
+            }
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+
+    if sidecar {
+        system_metrics::stop_sidecar_service();
+    }
+    Ok(())
+}
+
+async fn run(command: Command) -> Result<(), String> {
+    match command {
+        Command::Scan => print_json(&device_commands::scan_devices().await?),
+        Command::Info { ip } => print_json(&device_commands::get_device_info(ip).await?),
+        Command::Brightness { ip, value } => divoom_api::client(&ip)
+            .set_brightness(value)
+            .await
+            .map_err(|e| e.to_string()),
+        Command::Screen { ip, power } => divoom_api::client(&ip)
+            .set_screen_on(matches!(power, Power::On))
+            .await
+            .map_err(|e| e.to_string()),
+        Command::Upload { ip, source, screen } => {
+            let img = draw_commands::load_image(&source).await?;
+            draw_commands::send_image(&ip, &screen, img).await
+        }
+        Command::Panorama { ip, source, bezel } => {
+            draw_commands::upload_panorama(ip, source, Some(bezel)).await
+        }
+        Command::Text {
+            ip,
+            content,
+            screen,
+            id,
+            x,
+            y,
+            font,
+            color,
+            alignment,
+            width,
+            direction,
+            speed,
+        } => {
+            draw_commands::set_screen_text(
+                ip,
+                screen,
+                TextConfig {
+                    id,
+                    content,
+                    x,
+                    y,
+                    font,
+                    color,
+                    alignment,
+                    text_width: width,
+                    direction,
+                    speed,
+                },
+            )
+            .await
+        }
+        Command::ClearText { ip, screen } => draw_commands::clear_screen_text(ip, screen).await,
+        Command::PcMonitor {
+            ip,
+            lcd,
+            interval,
+            activate,
+            sidecar,
+        } => run_pc_monitor(ip, lcd, interval, activate, sidecar).await,
+        Command::Channel { ip, channel: None } => {
+            print_json(&channels::get_channel_selection(ip).await?)
+        }
+        Command::Channel {
+            ip,
+            channel: Some(channel),
+        } => channels::set_channel(ip, channel.into()).await,
+        Command::Clock { ip, clock_id, lcd } => {
+            channels::select_clock_face(ip, lcd, clock_id).await
+        }
+        Command::ClockFaces {
+            category: None,
+            page: _,
+        } => print_json(&channels::get_clock_face_types().await?),
+        Command::ClockFaces {
+            category: Some(category),
+            page,
+        } => print_json(&channels::get_clock_faces(category, page).await?),
+        Command::Weather { ip } => print_json(&weather::get_device_weather(ip).await?),
+        Command::Location {
+            ip,
+            longitude,
+            latitude,
+        } => weather::set_device_location(ip, longitude, latitude).await,
+        Command::SyncTime { ip } => time_sync::sync_device(&ip).await,
+        Command::Timer {
+            ip,
+            minutes,
+            seconds,
+            stop,
+        } => divoom_api::client(&ip)
+            .set_timer(minutes, seconds, !stop)
+            .await
+            .map_err(|e| e.to_string()),
+        Command::Stopwatch { ip, action } => divoom_api::client(&ip)
+            .set_stopwatch(action.into())
+            .await
+            .map_err(|e| e.to_string()),
+        Command::Score { ip, red, blue } => divoom_api::client(&ip)
+            .set_scoreboard(red, blue)
+            .await
+            .map_err(|e| e.to_string()),
+        Command::Noise { ip, power } => divoom_api::client(&ip)
+            .set_noise_meter(matches!(power, Power::On))
+            .await
+            .map_err(|e| e.to_string()),
+        Command::Scene { ip, name } => scenes::apply_scene(name, ip).await.map(|_| ()),
+        Command::Scenes => {
+            let names: Vec<String> = scenes::list_scenes()?.into_iter().map(|s| s.name).collect();
+            print_json(&names)
+        }
+        Command::Group { group_id, command } => run_group(group_id, command).await,
+        Command::Beep { ip, pattern } => {
+            device_commands::play_buzzer_pattern(ip, pattern.into()).await
+        }
+        Command::ResetPicId { ip } => pic_ids::reset_pic_id(ip).await,
+        Command::Reboot { ip } => divoom_api::client(&ip)
+            .reboot()
+            .await
+            .map_err(|e| e.to_string()),
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    dotenvy::dotenv().ok();
+    let cli = Cli::parse();
+
+    if let Some(dir) = cli.settings_dir {
+        app_settings::init(dir);
+    }
+    system_metrics::register_builtin_providers();
+
+    match run(cli.command).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
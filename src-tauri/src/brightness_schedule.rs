@@ -0,0 +1,413 @@
+use std::f64::consts::PI;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, Local, NaiveDate, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::app_settings;
+use crate::divoom_api;
+use crate::runtime;
+use crate::weather::Location;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+// Set when the schedule changes, so the entry currently in effect is applied right away
+static APPLY_REQUESTED: AtomicBool = AtomicBool::new(true);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ScheduleTime {
+    /// Local time of day.
+    At { hour: u32, minute: u32 },
+    /// Minutes after (or before, when negative) sunrise at the weather location.
+    Sunrise {
+        #[serde(default)]
+        offset_minutes: i64,
+    },
+    Sunset {
+        #[serde(default)]
+        offset_minutes: i64,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduleEntry {
+    pub enabled: bool,
+    pub time: ScheduleTime,
+    /// 0 - Sunday ... 6 - Saturday, as in cron; empty means every day.
+    #[serde(default)]
+    pub weekdays: Vec<u8>,
+    #[serde(default)]
+    pub brightness: Option<u8>,
+    #[serde(default)]
+    pub screen_on: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BrightnessSchedule {
+    pub enabled: bool,
+    /// IP addresses of the devices the schedule controls.
+    #[serde(default)]
+    pub devices: Vec<String>,
+    #[serde(default)]
+    pub entries: Vec<ScheduleEntry>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct SunTimes {
+    /// Unix time; `None` during polar day or night.
+    pub sunrise: Option<i64>,
+    pub sunset: Option<i64>,
+}
+
+/// Sunrise and sunset on `date` using the sunrise equation, accurate to a minute or two.
+fn sun_times(date: NaiveDate, location: Location) -> SunTimes {
+    let to_rad = PI / 180.0;
+    let julian_day = f64::from(date.num_days_from_ce() + 1_721_425);
+
+    let mean_solar_noon = julian_day - 2_451_545.0 + 0.0008 - location.longitude / 360.0;
+    let anomaly = (357.5291 + 0.985_600_28 * mean_solar_noon).rem_euclid(360.0) * to_rad;
+    let center =
+        1.9148 * anomaly.sin() + 0.02 * (2.0 * anomaly).sin() + 0.0003 * (3.0 * anomaly).sin();
+    let ecliptic_longitude =
+        (anomaly / to_rad + center + 180.0 + 102.9372).rem_euclid(360.0) * to_rad;
+    let transit = 2_451_545.0 + mean_solar_noon + 0.0053 * anomaly.sin()
+        - 0.0069 * (2.0 * ecliptic_longitude).sin();
+
+    let declination = (ecliptic_longitude.sin() * (23.4397 * to_rad).sin()).asin();
+    let latitude = location.latitude * to_rad;
+    let cos_hour_angle = ((-0.833 * to_rad).sin() - latitude.sin() * declination.sin())
+        / (latitude.cos() * declination.cos());
+
+    if !(-1.0..=1.0).contains(&cos_hour_angle) {
+        return SunTimes {
+            sunrise: None,
+            sunset: None,
+        };
+    }
+
+    let hour_angle = cos_hour_angle.acos() / to_rad / 360.0;
+    let to_unix = |julian: f64| ((julian - 2_440_587.5) * 86_400.0).round() as i64;
+
+    SunTimes {
+        sunrise: Some(to_unix(transit - hour_angle)),
+        sunset: Some(to_unix(transit + hour_angle)),
+    }
+}
+
+/// Checks times and weekdays of every entry, so entries that could never fire are
+/// rejected when saving.
+pub fn validate(schedule: &BrightnessSchedule) -> Result<(), String> {
+    for (index, entry) in schedule.entries.iter().enumerate() {
+        let problem = match entry.time {
+            ScheduleTime::At { hour, minute } if hour > 23 || minute > 59 => {
+                Some(format!("{}:{:02} is not a time of day", hour, minute))
+            }
+            ScheduleTime::Sunrise { offset_minutes } | ScheduleTime::Sunset { offset_minutes }
+                if offset_minutes.abs() > 12 * 60 =>
+            {
+                Some(format!(
+                    "offset of {} minutes is more than 12 hours",
+                    offset_minutes
+                ))
+            }
+            _ => entry
+                .weekdays
+                .iter()
+                .find(|day| **day > 6)
+                .map(|day| format!("weekday {} is not 0 (Sunday) to 6 (Saturday)", day)),
+        };
+
+        if let Some(problem) = problem {
+            return Err(format!("Schedule entry {}: {}", index + 1, problem));
+        }
+    }
+    Ok(())
+}
+
+/// When `entry` fires on `date`, or `None` if it does not fire that day.
+fn occurrence(
+    entry: &ScheduleEntry,
+    date: NaiveDate,
+    location: Option<Location>,
+) -> Option<DateTime<Local>> {
+    let weekday = date.weekday().num_days_from_sunday() as u8;
+    if !entry.enabled || (!entry.weekdays.is_empty() && !entry.weekdays.contains(&weekday)) {
+        return None;
+    }
+
+    match entry.time {
+        ScheduleTime::At { hour, minute } => Local
+            .from_local_datetime(&date.and_hms_opt(hour, minute, 0)?)
+            .earliest(),
+        ScheduleTime::Sunrise { offset_minutes } | ScheduleTime::Sunset { offset_minutes } => {
+            let sun = sun_times(date, location?);
+            let event = match entry.time {
+                ScheduleTime::Sunrise { .. } => sun.sunrise?,
+                _ => sun.sunset?,
+            };
+            let at = DateTime::<Utc>::from_timestamp(event + offset_minutes * 60, 0)?;
+            Some(at.with_timezone(&Local))
+        }
+    }
+}
+
+/// The last entry firing in `(after, until]`, looking at the days of both bounds.
+fn latest_due(
+    schedule: &BrightnessSchedule,
+    location: Option<Location>,
+    after: DateTime<Local>,
+    until: DateTime<Local>,
+) -> Option<&ScheduleEntry> {
+    let mut dates = vec![after.date_naive(), until.date_naive()];
+    dates.dedup();
+
+    dates
+        .into_iter()
+        .flat_map(|date| {
+            schedule
+                .entries
+                .iter()
+                .filter_map(move |entry| Some((occurrence(entry, date, location)?, entry)))
+        })
+        .filter(|(at, _)| *at > after && *at <= until)
+        .max_by_key(|(at, _)| *at)
+        .map(|(_, entry)| entry)
+}
+
+async fn apply_entry(ip_address: &str, entry: &ScheduleEntry) -> Result<(), String> {
+    let client = divoom_api::client(ip_address);
+
+    if let Some(on) = entry.screen_on {
+        client
+            .set_screen_on(on)
+            .await
+            .map_err(|e| format!("Failed to switch screen: {}", e))?;
+    }
+    if let Some(brightness) = entry.brightness {
+        client
+            .set_brightness(brightness)
+            .await
+            .map_err(|e| format!("Failed to set brightness: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Applies the entry in effect on the next check, e.g. after the schedule changed.
+pub fn request_apply() {
+    APPLY_REQUESTED.store(true, Ordering::Relaxed);
+}
+
+/// Starts the background job that applies schedule entries as their time comes.
+pub fn start() {
+    runtime::spawn(async {
+        let mut last_check = Local::now();
+
+        loop {
+            let schedule = app_settings::brightness_schedule();
+            let location = app_settings::weather_settings().location;
+            let now = Local::now();
+
+            // Catching up looks back a full day, so the last entry of yesterday still counts
+            let after = if APPLY_REQUESTED.swap(false, Ordering::Relaxed) {
+                now - chrono::Duration::days(1)
+            } else {
+                last_check
+            };
+            last_check = now;
+
+            if schedule.enabled {
+                if let Some(entry) = latest_due(&schedule, location, after, now) {
+                    for ip in &schedule.devices {
+                        if let Err(e) = apply_entry(ip, entry).await {
+                            eprintln!("[Brightness schedule] {}: {}", ip, e);
+                        }
+                    }
+                }
+            }
+
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+/// Today's sunrise and sunset at the weather location, if one is configured.
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_sun_times() -> Option<SunTimes> {
+    let location = app_settings::weather_settings().location?;
+    Some(sun_times(Local::now().date_naive(), location))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn utc(timestamp: Option<i64>) -> String {
+        DateTime::<Utc>::from_timestamp(timestamp.unwrap(), 0)
+            .unwrap()
+            .format("%H:%M")
+            .to_string()
+    }
+
+    fn minutes_between(a: Option<i64>, hour: u32, minute: u32, on: NaiveDate) -> i64 {
+        let expected = on
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+            .and_utc()
+            .timestamp();
+        (a.unwrap() - expected).abs() / 60
+    }
+
+    #[test]
+    fn sun_times_match_published_tables() {
+        let berlin = Location {
+            latitude: 52.52,
+            longitude: 13.405,
+        };
+        let midsummer = date(2024, 6, 21);
+        let sun = sun_times(midsummer, berlin);
+
+        // 04:43 and 21:33 CEST
+        assert!(
+            minutes_between(sun.sunrise, 2, 43, midsummer) <= 2,
+            "{}",
+            utc(sun.sunrise)
+        );
+        assert!(
+            minutes_between(sun.sunset, 19, 33, midsummer) <= 2,
+            "{}",
+            utc(sun.sunset)
+        );
+
+        let sydney = Location {
+            latitude: -33.87,
+            longitude: 151.21,
+        };
+        let sun = sun_times(midsummer, sydney);
+        // 07:00 AEST the same day and 16:54 AEST, i.e. 21:00 and 06:54 UTC
+        assert!(
+            minutes_between(sun.sunrise, 21, 0, date(2024, 6, 20)) <= 2,
+            "{}",
+            utc(sun.sunrise)
+        );
+        assert!(
+            minutes_between(sun.sunset, 6, 54, midsummer) <= 2,
+            "{}",
+            utc(sun.sunset)
+        );
+    }
+
+    #[test]
+    fn polar_day_and_night_have_no_sun_times() {
+        let tromso = Location {
+            latitude: 69.65,
+            longitude: 18.96,
+        };
+
+        for day in [date(2024, 6, 21), date(2024, 12, 21)] {
+            let sun = sun_times(day, tromso);
+            assert_eq!((sun.sunrise, sun.sunset), (None, None), "{}", day);
+        }
+        assert!(sun_times(date(2024, 3, 20), tromso).sunrise.is_some());
+    }
+
+    fn entry(time: ScheduleTime, weekdays: Vec<u8>) -> ScheduleEntry {
+        ScheduleEntry {
+            enabled: true,
+            time,
+            weekdays,
+            brightness: Some(50),
+            screen_on: None,
+        }
+    }
+
+    #[test]
+    fn weekdays_count_from_sunday() {
+        let at_nine = ScheduleTime::At { hour: 9, minute: 0 };
+        // June 16th 2024 is a Sunday
+        let sunday = date(2024, 6, 16);
+
+        assert!(occurrence(&entry(at_nine, vec![0]), sunday, None).is_some());
+        assert!(occurrence(&entry(at_nine, vec![1]), sunday, None).is_none());
+        assert!(occurrence(&entry(at_nine, vec![1]), date(2024, 6, 17), None).is_some());
+        assert!(occurrence(&entry(at_nine, vec![6]), date(2024, 6, 15), None).is_some());
+    }
+
+    #[test]
+    fn entries_that_never_fire_are_rejected() {
+        let schedule = |entries| BrightnessSchedule {
+            enabled: true,
+            devices: Vec::new(),
+            entries,
+        };
+        let valid = entry(
+            ScheduleTime::At {
+                hour: 23,
+                minute: 59,
+            },
+            vec![0, 6],
+        );
+        assert!(validate(&schedule(vec![valid])).is_ok());
+
+        for broken in [
+            entry(
+                ScheduleTime::At {
+                    hour: 25,
+                    minute: 0,
+                },
+                Vec::new(),
+            ),
+            entry(
+                ScheduleTime::At {
+                    hour: 9,
+                    minute: 60,
+                },
+                Vec::new(),
+            ),
+            entry(ScheduleTime::At { hour: 9, minute: 0 }, vec![7]),
+            entry(
+                ScheduleTime::Sunset {
+                    offset_minutes: -800,
+                },
+                Vec::new(),
+            ),
+        ] {
+            assert!(
+                validate(&schedule(vec![broken.clone()])).is_err(),
+                "{:?}",
+                broken
+            );
+        }
+    }
+
+    #[test]
+    fn sun_entries_do_not_fire_without_a_sunrise() {
+        let entry = ScheduleEntry {
+            enabled: true,
+            time: ScheduleTime::Sunrise { offset_minutes: 30 },
+            weekdays: Vec::new(),
+            brightness: Some(50),
+            screen_on: None,
+        };
+        let tromso = Location {
+            latitude: 69.65,
+            longitude: 18.96,
+        };
+
+        assert!(occurrence(&entry, date(2024, 6, 21), Some(tromso)).is_none());
+        assert!(occurrence(&entry, date(2024, 6, 21), None).is_none());
+
+        let berlin = Location {
+            latitude: 52.52,
+            longitude: 13.405,
+        };
+        let sunrise = sun_times(date(2024, 6, 21), berlin).sunrise.unwrap();
+        let at = occurrence(&entry, date(2024, 6, 21), Some(berlin)).unwrap();
+        assert_eq!(at.timestamp(), sunrise + 30 * 60);
+    }
+}
@@ -0,0 +1,154 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use divoom::{Channel, ChannelSelection, ClockFacePage, CloudChannel, PC_MONITOR_CLOCK_ID};
+use serde::{Deserialize, Serialize};
+
+use crate::app_settings;
+use crate::divoom_api;
+use crate::draw_commands;
+
+// Clock face shown before the tray switched an LCD to PC Monitor, per (IP, LCD)
+static PREVIOUS_CLOCKS: Mutex<BTreeMap<(String, u32), u64>> = Mutex::new(BTreeMap::new());
+
+/// LCD the tray menu flips between a clock face and PC Monitor.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrayChannelSettings {
+    pub ip_address: String,
+    pub lcd_index: u32,
+    /// Face restored by the tray; defaults to the one shown before PC Monitor.
+    #[serde(default)]
+    pub clock_id: Option<u64>,
+}
+
+/// Switches one LCD to `clock_id`, looking up the device's LCD layout first.
+///
+/// Returns the face the LCD showed before, when the cloud knows it.
+async fn switch_clock(
+    ip_address: &str,
+    lcd_index: u32,
+    clock_id: u64,
+) -> Result<Option<u64>, String> {
+    let info = draw_commands::get_lcd_info(ip_address.to_string()).await?;
+    let independence = info
+        .independence_list
+        .first()
+        .ok_or("Device reported no LCD layout")?;
+    let previous = independence
+        .lcd_list
+        .get(lcd_index as usize)
+        .map(|lcd| lcd.lcd_clock_id);
+
+    divoom_api::client(ip_address)
+        .select_clock(
+            info.device_id,
+            independence.lcd_independence,
+            lcd_index,
+            clock_id,
+        )
+        .await
+        .map_err(|e| format!("Failed to select clock face: {}", e))?;
+
+    Ok(previous)
+}
+
+fn tray_target() -> Result<TrayChannelSettings, String> {
+    app_settings::tray_channel_settings()
+        .ok_or_else(|| "No device is configured for the tray".to_string())
+}
+
+/// Tray action: shows PC Monitor on the configured LCD.
+pub async fn show_pc_monitor() -> Result<(), String> {
+    let target = tray_target()?;
+    let previous = switch_clock(&target.ip_address, target.lcd_index, PC_MONITOR_CLOCK_ID).await?;
+
+    if let Some(clock_id) = previous.filter(|id| *id != PC_MONITOR_CLOCK_ID) {
+        if let Ok(mut clocks) = PREVIOUS_CLOCKS.lock() {
+            clocks.insert((target.ip_address, target.lcd_index), clock_id);
+        }
+    }
+    Ok(())
+}
+
+/// Tray action: brings back the configured or previously shown clock face.
+pub async fn show_clock_face() -> Result<(), String> {
+    let target = tray_target()?;
+    let remembered = PREVIOUS_CLOCKS.lock().ok().and_then(|clocks| {
+        clocks
+            .get(&(target.ip_address.clone(), target.lcd_index))
+            .copied()
+    });
+    let clock_id = target
+        .clock_id
+        .or(remembered)
+        .ok_or("No clock face to return to; pick one in the tray settings")?;
+
+    switch_clock(&target.ip_address, target.lcd_index, clock_id)
+        .await
+        .map(|_| ())
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn set_channel(ip_address: String, channel: Channel) -> Result<(), String> {
+    divoom_api::client(&ip_address)
+        .set_channel(channel)
+        .await
+        .map_err(|e| format!("Failed to switch channel: {}", e))
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn get_channel_selection(ip_address: String) -> Result<ChannelSelection, String> {
+    divoom_api::client(&ip_address)
+        .get_channel_selection()
+        .await
+        .map_err(|e| format!("Failed to read channel: {}", e))
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn set_custom_page(ip_address: String, index: u8) -> Result<(), String> {
+    divoom_api::client(&ip_address)
+        .set_custom_page(index)
+        .await
+        .map_err(|e| format!("Failed to select custom page: {}", e))
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn set_visualizer(ip_address: String, position: u8) -> Result<(), String> {
+    divoom_api::client(&ip_address)
+        .set_visualizer(position)
+        .await
+        .map_err(|e| format!("Failed to select visualizer: {}", e))
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn set_cloud_channel(ip_address: String, channel: CloudChannel) -> Result<(), String> {
+    divoom_api::client(&ip_address)
+        .set_cloud_channel(channel)
+        .await
+        .map_err(|e| format!("Failed to select cloud channel: {}", e))
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn select_clock_face(
+    ip_address: String,
+    lcd_index: u32,
+    clock_id: u64,
+) -> Result<(), String> {
+    switch_clock(&ip_address, lcd_index, clock_id)
+        .await
+        .map(|_| ())
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn get_clock_face_types() -> Result<Vec<String>, String> {
+    divoom::cloud::clock_face_types()
+        .await
+        .map_err(|e| format!("Failed to load clock face types: {}", e))
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn get_clock_faces(dial_type: String, page: u32) -> Result<ClockFacePage, String> {
+    divoom::cloud::clock_faces(&dial_type, page)
+        .await
+        .map_err(|e| format!("Failed to load clock faces: {}", e))
+}
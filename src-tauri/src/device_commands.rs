@@ -1,149 +1,260 @@
-use serde_json::Number;
-
-use crate::divoom_api::{discover_via_divoom_api, send_command};
-use crate::models::{DeviceSettings, DivoomDevice};
-
-#[tauri::command]
-pub async fn scan_devices() -> Result<Vec<DivoomDevice>, String> {
-    let mut devices = Vec::new();
-
-    if let Ok(api_devices) = discover_via_divoom_api().await {
-        devices.extend(api_devices);
-    }
-
-    let mut unique_devices = Vec::new();
-    for device in devices {
-        let is_duplicate = unique_devices.iter().any(|d: &DivoomDevice| {
-            (device.ip_address.is_some() && d.ip_address == device.ip_address)
-                || (device.mac_address.is_some() && d.mac_address == device.mac_address)
-        });
-        if !is_duplicate {
-            unique_devices.push(device);
-        }
-    }
-
-    Ok(unique_devices)
-}
-
-#[tauri::command]
-pub async fn get_device_info(ip_address: String) -> Result<DeviceSettings, String> {
-    let result = send_command(
-        &ip_address,
-        &serde_json::json!({
-            "Command": "Channel/GetAllConf"
-        }),
-    )
-    .await
-    .map_err(|e| format!("Failed to send command: {}", e))?;
-
-    Ok(DeviceSettings {
-        brightness: result
-            .get("Brightness")
-            .and_then(|v| v.as_u64())
-            .map(|v| v as u8),
-        rotation_flag: result
-            .get("RotationFlag")
-            .and_then(|v| v.as_u64())
-            .map(|v| v as u8),
-        date_format: result
-            .get("DateFormat")
-            .and_then(|v| v.as_str())
-            .map(|s| s.to_string()),
-        time24_flag: result
-            .get("Time24Flag")
-            .and_then(|v| v.as_u64())
-            .map(|v| v as u8),
-        temperature_mode: result
-            .get("TemperatureMode")
-            .and_then(|v| v.as_u64())
-            .map(|v| v as u8),
-        mirror_flag: result
-            .get("MirrorFlag")
-            .and_then(|v| v.as_u64())
-            .map(|v| v as u8),
-        light_switch: result
-            .get("LightSwitch")
-            .and_then(|v| v.as_u64())
-            .map(|v| v as u8),
-    })
-}
-
-#[tauri::command]
-pub async fn set_brightness(ip_address: String, value: Number) {
-    let _ = send_command(
-        &ip_address,
-        &serde_json::json!({
-            "Command": "Channel/SetBrightness",
-            "Brightness": value
-        }),
-    )
-    .await
-    .map_err(|e| format!("Failed to send command: {}", e));
-}
-
-#[tauri::command]
-pub async fn set_switch_screen(ip_address: String, value: Number) {
-    let _ = send_command(
-        &ip_address,
-        &serde_json::json!({
-            "Command": "Channel/OnOffScreen",
-            "OnOff": value
-        }),
-    )
-    .await
-    .map_err(|e| format!("Failed to send command: {}", e));
-}
-
-#[tauri::command]
-pub async fn set_temperature_mode(ip_address: String, value: Number) {
-    let _ = send_command(
-        &ip_address,
-        &serde_json::json!({
-            "Command": "Device/SetDisTempMode",
-            // 0 - celsius, 1 - fahrenheit
-            "Mode": value
-        }),
-    )
-    .await
-    .map_err(|e| format!("Failed to send command: {}", e));
-}
-
-#[tauri::command]
-pub async fn set_mirror_mode(ip_address: String, value: Number) {
-    let _ = send_command(
-        &ip_address,
-        &serde_json::json!({
-            "Command": "Device/SetMirrorMode",
-            // 0 - disable, 1 - enable
-            "Mode": value
-        }),
-    )
-    .await
-    .map_err(|e| format!("Failed to send command: {}", e));
-}
-
-#[tauri::command]
-pub async fn set_24_hours_mode(ip_address: String, value: Number) {
-    let _ = send_command(
-        &ip_address,
-        &serde_json::json!({
-            "Command": "Device/SetTime24Flag",
-            // 0 - 0:12, 1 - 1:24
-            "Mode": value
-        }),
-    )
-    .await
-    .map_err(|e| format!("Failed to send command: {}", e));
-}
-
-#[tauri::command]
-pub async fn reboot_device(ip_address: String) {
-    let _ = send_command(
-        &ip_address,
-        &serde_json::json!({
-            "Command": "Device/SysReboot",
-        }),
-    )
-    .await
-    .map_err(|e| format!("Failed to send command: {}", e));
-}
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::divoom_api::{self, discover_via_divoom_api};
+use crate::models::{BuzzerPattern, BuzzerTiming, DeviceSettings, DivoomDevice, TextConfig};
+
+// Temperature unit per device IP, so metric pushes don't query the device every tick
+static TEMPERATURE_MODES: Mutex<BTreeMap<String, (Instant, u8)>> = Mutex::new(BTreeMap::new());
+const TEMPERATURE_MODE_TTL: Duration = Duration::from_secs(60);
+
+// LCD count and resolution per device IP from the cloud; `None` when the lookup
+// failed, retried later
+static PANELS: Mutex<BTreeMap<String, (Instant, Option<Panels>)>> = Mutex::new(BTreeMap::new());
+const LCD_LOOKUP_RETRY: Duration = Duration::from_secs(600);
+const LCD_LOOKUP_TIMEOUT: Duration = Duration::from_secs(3);
+
+fn remember_temperature_mode(ip_address: &str, mode: u8) {
+    if let Ok(mut modes) = TEMPERATURE_MODES.lock() {
+        modes.insert(ip_address.to_string(), (Instant::now(), mode));
+    }
+}
+
+/// Returns the device's temperature unit: 0 - celsius, 1 - fahrenheit.
+pub async fn temperature_mode(ip_address: &str) -> u8 {
+    if let Ok(modes) = TEMPERATURE_MODES.lock() {
+        if let Some((fetched_at, mode)) = modes.get(ip_address) {
+            if fetched_at.elapsed() < TEMPERATURE_MODE_TTL {
+                return *mode;
+            }
+        }
+    }
+
+    let mode = divoom_api::client(ip_address)
+        .get_all_conf()
+        .await
+        .ok()
+        .and_then(|settings| settings.temperature_mode)
+        .unwrap_or(0);
+    remember_temperature_mode(ip_address, mode);
+    mode
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Panels {
+    count: usize,
+    resolution: u32,
+}
+
+fn remember_panels(devices: &[DivoomDevice]) {
+    if let Ok(mut cache) = PANELS.lock() {
+        for device in devices {
+            if let Some(ip_address) = &device.ip_address {
+                let panels = Panels {
+                    count: device.lcd_count(),
+                    resolution: device.resolution(),
+                };
+                cache.insert(ip_address.clone(), (Instant::now(), Some(panels)));
+            }
+        }
+    }
+}
+
+fn cached_panels(ip_address: &str) -> Option<Option<Panels>> {
+    let cache = PANELS.lock().ok()?;
+    let (fetched_at, panels) = cache.get(ip_address)?;
+    (panels.is_some() || fetched_at.elapsed() < LCD_LOOKUP_RETRY).then_some(*panels)
+}
+
+/// LCD layout of the device as the cloud reports it; `None` for unknown devices.
+///
+/// A device that is not cached yet is looked up right away, but for at most
+/// `LCD_LOOKUP_TIMEOUT`; when that fails the device stays unchecked for a while.
+async fn panels(ip_address: &str) -> Option<Panels> {
+    if let Some(panels) = cached_panels(ip_address) {
+        return panels;
+    }
+
+    if let Ok(Ok(devices)) =
+        tokio::time::timeout(LCD_LOOKUP_TIMEOUT, discover_via_divoom_api()).await
+    {
+        remember_panels(&devices);
+    }
+
+    let mut cache = PANELS.lock().ok()?;
+    let (_, panels) = cache
+        .entry(ip_address.to_string())
+        .and_modify(|entry| {
+            if entry.1.is_none() {
+                entry.0 = Instant::now();
+            }
+        })
+        .or_insert((Instant::now(), None));
+    *panels
+}
+
+/// Checks `lcds` against the LCDs the device has.
+///
+/// Devices the cloud does not know are only checked against the Times Gate's five
+/// LCDs, by the client.
+pub async fn check_lcds(ip_address: &str, lcds: &[u32]) -> Result<(), String> {
+    if lcds.is_empty() {
+        return Err("No target screen selected".to_string());
+    }
+    let Some(Panels { count, .. }) = panels(ip_address).await else {
+        return Ok(());
+    };
+
+    match lcds.iter().find(|&&lcd| lcd as usize >= count) {
+        Some(lcd) if count == 1 => Err(format!(
+            "Screen {} does not exist: {} has a single screen (0)",
+            lcd, ip_address
+        )),
+        Some(lcd) => Err(format!(
+            "Screen {} does not exist: {} has screens 0-{}",
+            lcd,
+            ip_address,
+            count - 1
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Width and height of the device's LCDs in pixels; the Times Gate's for devices the
+/// cloud does not know.
+pub async fn resolution(ip_address: &str) -> u32 {
+    panels(ip_address)
+        .await
+        .map_or(divoom::LCD_SIZE, |panels| panels.resolution)
+}
+
+/// Image uploads are encoded the Times Gate's way (128x128 JPEG frames per LCD);
+/// devices the cloud reports with other panels, such as a Pixoo, are refused.
+pub async fn check_image_uploads(ip_address: &str) -> Result<(), String> {
+    match panels(ip_address).await {
+        Some(Panels { resolution, .. }) if resolution != divoom::LCD_SIZE => Err(format!(
+            "{} has {}x{} pixel screens, images can only be uploaded to {}x{} LCDs",
+            ip_address,
+            resolution,
+            resolution,
+            divoom::LCD_SIZE,
+            divoom::LCD_SIZE
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Checks the position and width of `texts` against the device's resolution.
+///
+/// Devices the cloud does not know are only checked against the Times Gate's LCD
+/// size, by the client.
+pub async fn check_texts<'a>(
+    ip_address: &str,
+    texts: impl IntoIterator<Item = &'a TextConfig>,
+) -> Result<(), String> {
+    let Some(Panels { resolution, .. }) = panels(ip_address).await else {
+        return Ok(());
+    };
+
+    texts
+        .into_iter()
+        .try_for_each(|text| text.validate(resolution))
+        .map_err(|e| e.to_string())
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn scan_devices() -> Result<Vec<DivoomDevice>, String> {
+    let mut devices = Vec::new();
+
+    if let Ok(api_devices) = discover_via_divoom_api().await {
+        remember_panels(&api_devices);
+        devices.extend(api_devices);
+    }
+
+    let mut unique_devices = Vec::new();
+    for device in devices {
+        let is_duplicate = unique_devices.iter().any(|d: &DivoomDevice| {
+            (device.ip_address.is_some() && d.ip_address == device.ip_address)
+                || (device.mac_address.is_some() && d.mac_address == device.mac_address)
+        });
+        if !is_duplicate {
+            unique_devices.push(device);
+        }
+    }
+
+    Ok(unique_devices)
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn get_device_info(ip_address: String) -> Result<DeviceSettings, String> {
+    divoom_api::client(&ip_address)
+        .get_device_state()
+        .await
+        .map_err(|e| format!("Failed to send command: {}", e))
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn set_brightness(ip_address: String, value: u8) -> Result<(), String> {
+    divoom_api::client(&ip_address)
+        .set_brightness(value)
+        .await
+        .map_err(|e| format!("Failed to set brightness: {}", e))
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn set_switch_screen(ip_address: String, value: u8) -> Result<(), String> {
+    divoom_api::client(&ip_address)
+        .set_screen_on(value != 0)
+        .await
+        .map_err(|e| format!("Failed to switch screen: {}", e))
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn set_temperature_mode(ip_address: String, value: u8) -> Result<(), String> {
+    // 0 - celsius, 1 - fahrenheit
+    divoom_api::client(&ip_address)
+        .set_temperature_mode(value == 1)
+        .await
+        .map_err(|e| format!("Failed to set temperature mode: {}", e))?;
+
+    remember_temperature_mode(&ip_address, value);
+    Ok(())
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn set_mirror_mode(ip_address: String, value: u8) -> Result<(), String> {
+    divoom_api::client(&ip_address)
+        .set_mirror_mode(value != 0)
+        .await
+        .map_err(|e| format!("Failed to set mirror mode: {}", e))
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn set_24_hours_mode(ip_address: String, value: u8) -> Result<(), String> {
+    divoom_api::client(&ip_address)
+        .set_24_hour_mode(value != 0)
+        .await
+        .map_err(|e| format!("Failed to set time format: {}", e))
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn play_buzzer(ip_address: String, timing: BuzzerTiming) -> Result<(), String> {
+    divoom_api::client(&ip_address)
+        .play_buzzer(timing)
+        .await
+        .map_err(|e| format!("Failed to play buzzer: {}", e))
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn play_buzzer_pattern(ip_address: String, pattern: BuzzerPattern) -> Result<(), String> {
+    play_buzzer(ip_address, pattern.timing()).await
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn reboot_device(ip_address: String) -> Result<(), String> {
+    divoom_api::client(&ip_address)
+        .reboot()
+        .await
+        .map_err(|e| format!("Failed to reboot device: {}", e))
+}
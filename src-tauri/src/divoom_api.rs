@@ -1,122 +1,72 @@
-use std::time::Duration;
-
-use crate::models::DivoomDevice;
-
-pub async fn send_command(
-    ip: &str,
-    command: &serde_json::Value,
-) -> Result<serde_json::Value, String> {
-    send_command_with_timeout(ip, command, Duration::from_millis(500)).await
-}
-
-pub async fn send_command_with_timeout(
-    ip: &str,
-    command: &serde_json::Value,
-    timeout: Duration,
-) -> Result<serde_json::Value, String> {
-    let client = reqwest::Client::builder()
-        .timeout(timeout)
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
-
-    let response = client
-        .post(format!("http://{}/post", ip))
-        .json(command)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to send command: {}", e))?;
-
-    if !response.status().is_success() {
-        return Err(format!("Command failed with status: {}", response.status()));
-    }
-
-    let result: serde_json::Value = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse response: {}", e))?;
-
-    Ok(result)
-}
-
-pub async fn discover_via_divoom_api() -> Result<Vec<DivoomDevice>, String> {
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(10))
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
-
-    let response = client
-        .post("https://app.divoom-gz.com/Device/ReturnSameLANDevice")
-        .send()
-        .await
-        .map_err(|e| format!("Failed to request Divoom API: {}", e))?;
-
-    if !response.status().is_success() {
-        return Err(format!(
-            "Divoom API returned status: {}",
-            response.status()
-        ));
-    }
-
-    let json: serde_json::Value = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse API response: {}", e))?;
-
-    let mut devices = Vec::new();
-
-    if let Some(device_list) = json.get("DeviceList").and_then(|v| v.as_array()) {
-        for device_json in device_list {
-            let name = device_json
-                .get("DeviceName")
-                .and_then(|v| v.as_str())
-                .unwrap_or("Unknown Device")
-                .to_string();
-
-            let ip_address = device_json
-                .get("DevicePrivateIP")
-                .and_then(|v| v.as_str())
-                .map(|s| s.to_string());
-
-            let mac_address = device_json
-                .get("DeviceMac")
-                .and_then(|v| v.as_str())
-                .map(|s| s.to_string());
-
-            let hardware = device_json
-                .get("Hardware")
-                .and_then(|v| v.as_u64())
-                .unwrap_or(0);
-
-            let device_id = device_json
-                .get("DeviceId")
-                .and_then(|v| v.as_u64())
-                .unwrap_or(0);
-
-            let device_type = match hardware {
-                400 => "Times Gate",
-                401 => "Pixoo 64",
-                402 => "Pixoo 32",
-                403 => "Pixoo 16",
-                404 => "Ditoo",
-                405 => "Ditoo Plus",
-                406 => "Ditoo Pro",
-                407 => "Pixoo Max",
-                408 => "Pixoo Mini",
-                _ => "Unknown Divoom Device",
-            }
-            .to_string();
-
-            devices.push(DivoomDevice {
-                name,
-                mac_address,
-                device_type,
-                ip_address,
-                signal_strength: None,
-                is_connected: true,
-                device_id: Some(device_id),
-            });
-        }
-    }
-
-    Ok(devices)
-}
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use divoom::{CommandObserver, DivoomClient};
+use serde::Serialize;
+
+use crate::models::DivoomDevice;
+
+static DEVICE_HEALTH: Mutex<BTreeMap<String, DeviceHealth>> = Mutex::new(BTreeMap::new());
+
+/// Per-device statistics of commands sent over the local HTTP API.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DeviceHealth {
+    pub last_latency_ms: Option<u64>,
+    pub last_success: Option<bool>,
+    pub last_error: Option<String>,
+    /// Unix time of the last finished command, in seconds.
+    pub last_command_at: Option<u64>,
+    pub in_flight: u32,
+    pub success_total: u64,
+    pub error_total: u64,
+}
+
+pub fn device_health() -> BTreeMap<String, DeviceHealth> {
+    DEVICE_HEALTH.lock().map(|h| h.clone()).unwrap_or_default()
+}
+
+struct HealthObserver;
+
+impl CommandObserver for HealthObserver {
+    fn started(&self, ip: &str) {
+        if let Ok(mut health) = DEVICE_HEALTH.lock() {
+            health.entry(ip.to_string()).or_default().in_flight += 1;
+        }
+    }
+
+    fn finished(&self, ip: &str, latency: Duration, result: Result<(), &divoom::Error>) {
+        if let Ok(mut health) = DEVICE_HEALTH.lock() {
+            let entry = health.entry(ip.to_string()).or_default();
+            entry.in_flight = entry.in_flight.saturating_sub(1);
+            entry.last_latency_ms = Some(latency.as_millis() as u64);
+            entry.last_command_at = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .ok()
+                .map(|d| d.as_secs());
+            match result {
+                Ok(()) => {
+                    entry.last_success = Some(true);
+                    entry.last_error = None;
+                    entry.success_total += 1;
+                }
+                Err(e) => {
+                    entry.last_success = Some(false);
+                    entry.last_error = Some(e.to_string());
+                    entry.error_total += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Client for one device that records its commands in the device health statistics.
+pub fn client(ip: &str) -> DivoomClient {
+    DivoomClient::new(ip).with_observer(Arc::new(HealthObserver))
+}
+
+pub async fn discover_via_divoom_api() -> Result<Vec<DivoomDevice>, String> {
+    divoom::cloud::discover_lan_devices()
+        .await
+        .map_err(|e| format!("Failed to request Divoom API: {}", e))
+}
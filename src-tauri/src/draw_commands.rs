@@ -1,286 +1,328 @@
-use base64::{engine::general_purpose, Engine as _};
-use image::codecs::jpeg::JpegEncoder;
-use image::{DynamicImage, ImageEncoder};
-use std::path::Path;
-use std::sync::atomic::{AtomicU32, Ordering};
-use std::time::Duration;
-
-use crate::divoom_api::{discover_via_divoom_api, send_command, send_command_with_timeout};
-use crate::models::{LcdIndependenceInfo, LcdInfo, LcdInfoResponse, TextConfig};
-
-static PIC_ID_COUNTER: AtomicU32 = AtomicU32::new(1000);
-
-fn get_next_pic_id() -> u32 {
-    PIC_ID_COUNTER.fetch_add(1, Ordering::Relaxed)
-}
-
-fn resize_image(img: DynamicImage, max_width: u32, max_height: u32) -> Result<Vec<u8>, String> {
-    let resized = img.resize_exact(max_width, max_height, image::imageops::FilterType::Lanczos3);
-    let rgba = resized.to_rgba8();
-    let mut buffer = Vec::new();
-    {
-        let encoder = JpegEncoder::new(&mut buffer);
-        encoder
-            .write_image(
-                rgba.as_raw(),
-                rgba.width(),
-                rgba.height(),
-                image::ColorType::Rgba8,
-            )
-            .map_err(|e| format!("Failed to encode image: {}", e))?;
-    }
-
-    Ok(buffer)
-}
-
-async fn load_image_from_url(url: &str) -> Result<DynamicImage, String> {
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(30))
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
-
-    let response = client
-        .get(url)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to download image: {}", e))?;
-
-    if !response.status().is_success() {
-        return Err(format!("Failed to download image: {}", response.status()));
-    }
-
-    let bytes = response
-        .bytes()
-        .await
-        .map_err(|e| format!("Failed to read image bytes: {}", e))?;
-
-    image::load_from_memory(&bytes).map_err(|e| format!("Failed to decode image: {}", e))
-}
-
-async fn load_image_from_file(file_path: &str) -> Result<DynamicImage, String> {
-    image::open(Path::new(file_path)).map_err(|e| format!("Failed to open image file: {}", e))
-}
-
-#[tauri::command]
-pub async fn upload_image_from_url(
-    ip_address: String,
-    screen_index: u32,
-    url: String,
-) -> Result<(), String> {
-    let img = load_image_from_url(&url).await?;
-    let image_data = resize_image(img, 128, 128)?;
-    let base64_data = general_purpose::STANDARD.encode(&image_data);
-
-    let mut lcd_array = [0u8; 5];
-    if screen_index < 5 {
-        lcd_array[screen_index as usize] = 1;
-    }
-
-    let pic_id = get_next_pic_id();
-
-    send_command_with_timeout(
-        &ip_address,
-        &serde_json::json!({
-            "Command": "Draw/SendHttpGif",
-            "LCDArray": lcd_array,
-            "PicNum": 1,
-            "PicWidth": 128,
-            "PicOffset": 0,
-            "PicID": pic_id,
-            "PicSpeed": 1000,
-            "PicData": base64_data
-        }),
-        Duration::from_secs(1),
-    )
-    .await
-    .map_err(|e| format!("Failed to send image command: {}", e))?;
-
-    Ok(())
-}
-
-#[tauri::command]
-pub async fn upload_image_from_file(
-    ip_address: String,
-    screen_index: u32,
-    file_path: String,
-) -> Result<(), String> {
-    let img = load_image_from_file(&file_path).await?;
-    let image_data = resize_image(img, 128, 128)?;
-    let base64_data = general_purpose::STANDARD.encode(&image_data);
-
-    let mut lcd_array = [0u8; 5];
-    if screen_index < 5 {
-        lcd_array[screen_index as usize] = 1;
-    }
-
-    let pic_id = get_next_pic_id();
-
-    send_command_with_timeout(
-        &ip_address,
-        &serde_json::json!({
-            "Command": "Draw/SendHttpGif",
-            "LCDArray": lcd_array,
-            "PicNum": 1,
-            "PicWidth": 128,
-            "PicOffset": 0,
-            "PicID": pic_id,
-            "PicSpeed": 1000,
-            "PicData": base64_data
-        }),
-        Duration::from_secs(1),
-    )
-    .await
-    .map_err(|e| format!("Failed to send image command: {}", e))?;
-
-    Ok(())
-}
-
-#[tauri::command]
-pub async fn set_screen_text(
-    ip_address: String,
-    screen_index: u32,
-    text_config: TextConfig,
-) -> Result<(), String> {
-    let color = text_config
-        .color
-        .unwrap_or_else(|| "255,255,255".to_string());
-    let font = text_config.font.unwrap_or(7);
-    let alignment = text_config.alignment.unwrap_or(0);
-    let text_width = text_config.text_width.unwrap_or(64);
-
-    send_command(
-        &ip_address,
-        &serde_json::json!({
-            "Command": "Draw/SendHttpText",
-            "LcdIndex": screen_index,
-            "TextId": text_config.id,
-            "x": text_config.x,
-            "y": text_config.y,
-            "dir": 0,
-            "font": font,
-            "TextWidth": text_width,
-            "speed": 100,
-            "TextString": text_config.content,
-            "color": color,
-            "align": alignment
-        }),
-    )
-    .await
-    .map_err(|e| format!("Failed to send text command: {}", e))?;
-
-    Ok(())
-}
-
-#[tauri::command]
-pub async fn get_lcd_info(ip_address: String) -> Result<LcdInfoResponse, String> {
-    let devices = discover_via_divoom_api().await?;
-
-    let device = devices
-        .iter()
-        .find(|d| d.ip_address.as_deref() == Some(&ip_address))
-        .ok_or_else(|| format!("Device with IP {} not found", ip_address))?;
-
-    let device_id = device.device_id.ok_or("Device has no ID")?;
-
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(10))
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
-
-    let url = format!(
-        "https://app.divoom-gz.com/Channel/Get5LcdInfoV2?DeviceType=LCD&DeviceId={}",
-        device_id
-    );
-
-    let response = client
-        .get(&url)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to request LCD info: {}", e))?;
-
-    if !response.status().is_success() {
-        return Err(format!(
-            "LCD info API returned status: {}",
-            response.status()
-        ));
-    }
-
-    let json: serde_json::Value = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse LCD info response: {}", e))?;
-
-    let mut independence_list = Vec::new();
-
-    if let Some(list) = json.get("LcdIndependenceList").and_then(|v| v.as_array()) {
-        for item in list {
-            let lcd_independence = item
-                .get("LcdIndependence")
-                .and_then(|v| v.as_u64())
-                .unwrap_or(0);
-
-            let mut lcd_list = Vec::new();
-            if let Some(lcds) = item.get("LcdList").and_then(|v| v.as_array()) {
-                for lcd in lcds {
-                    let lcd_clock_id = lcd.get("LcdClockId").and_then(|v| v.as_u64()).unwrap_or(0);
-                    lcd_list.push(LcdInfo { lcd_clock_id });
-                }
-            }
-
-            independence_list.push(LcdIndependenceInfo {
-                lcd_independence,
-                lcd_list,
-            });
-        }
-    }
-
-    Ok(LcdInfoResponse {
-        device_id,
-        independence_list,
-    })
-}
-
-#[tauri::command]
-pub async fn activate_pc_monitor(
-    ip_address: String,
-    device_id: u64,
-    lcd_independence: u64,
-    lcd_index: u32,
-) -> Result<(), String> {
-    send_command(
-        &ip_address,
-        &serde_json::json!({
-            "Command": "Channel/SetClockSelectId",
-            "LcdIndependence": lcd_independence,
-            "DeviceId": device_id,
-            "LcdIndex": lcd_index,
-            "ClockId": 625 // PC Monitor clock
-        }),
-    )
-    .await
-    .map_err(|e| format!("Failed to activate PC monitor: {}", e))?;
-
-    Ok(())
-}
-
-#[tauri::command]
-pub async fn send_pc_metrics(
-    ip_address: String,
-    lcd_index: u32,
-    disp_data: Vec<String>,
-) -> Result<(), String> {
-    send_command(
-        &ip_address,
-        &serde_json::json!({
-            "Command": "Device/UpdatePCParaInfo",
-            "ScreenList": [{
-                "LcdId": lcd_index,
-                "DispData": disp_data
-            }]
-        }),
-    )
-    .await
-    .map_err(|e| format!("Failed to send PC metrics: {}", e))?;
-
-    Ok(())
-}
+use image::codecs::gif::GifDecoder;
+use image::imageops::FilterType;
+use image::{AnimationDecoder, DynamicImage, ImageFormat};
+use std::io::Cursor;
+use std::time::Duration;
+
+use crate::device_commands;
+use crate::divoom_api::{self, discover_via_divoom_api};
+use crate::metric_pipeline;
+use crate::metric_providers;
+use crate::models::{DisplayItem, LcdInfoResponse, TextConfig};
+use crate::pic_ids;
+use crate::system_metrics::SystemMetrics;
+
+/// Frames of a still image or an animated GIF.
+pub struct Animation {
+    pub frames: Vec<DynamicImage>,
+    pub speed_ms: u32,
+}
+
+async fn download(url: &str) -> Result<Vec<u8>, String> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to download image: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Failed to download image: {}", response.status()));
+    }
+
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to read image bytes: {}", e))?;
+
+    Ok(bytes.to_vec())
+}
+
+async fn load_image_from_url(url: &str) -> Result<DynamicImage, String> {
+    let bytes = download(url).await?;
+    image::load_from_memory(&bytes).map_err(|e| format!("Failed to decode image: {}", e))
+}
+
+async fn load_image_from_file(file_path: &str) -> Result<DynamicImage, String> {
+    let bytes = tokio::fs::read(file_path)
+        .await
+        .map_err(|e| format!("Failed to open image file: {}", e))?;
+    image::load_from_memory(&bytes).map_err(|e| format!("Failed to decode image: {}", e))
+}
+
+/// Shows `img` on every screen in `screen_indexes`, after checking the device has them.
+pub async fn send_image(
+    ip_address: &str,
+    screen_indexes: &[u32],
+    img: DynamicImage,
+) -> Result<(), String> {
+    device_commands::check_lcds(ip_address, screen_indexes).await?;
+    device_commands::check_image_uploads(ip_address).await?;
+    let pic_id = pic_ids::allocate(ip_address, 1).await?;
+    divoom_api::client(ip_address)
+        .send_image(screen_indexes, pic_id, &img)
+        .await
+        .map_err(|e| format!("Failed to send image command: {}", e))
+}
+
+/// Fills a whole LCD with one color.
+pub async fn send_solid_color(
+    ip_address: &str,
+    screen_index: u32,
+    rgb: [u8; 3],
+) -> Result<(), String> {
+    let img = image::RgbImage::from_pixel(128, 128, image::Rgb(rgb));
+    send_image(ip_address, &[screen_index], DynamicImage::ImageRgb8(img)).await
+}
+
+/// Loads an image from an http(s) URL or a local file path.
+pub async fn load_image(source: &str) -> Result<DynamicImage, String> {
+    if source.starts_with("http://") || source.starts_with("https://") {
+        load_image_from_url(source).await
+    } else {
+        load_image_from_file(source).await
+    }
+}
+
+/// Loads an http(s) URL or a local file; GIFs keep up to `divoom::MAX_FRAMES` frames.
+pub async fn load_animation(source: &str) -> Result<Animation, String> {
+    let bytes = if source.starts_with("http://") || source.starts_with("https://") {
+        download(source).await?
+    } else {
+        tokio::fs::read(source)
+            .await
+            .map_err(|e| format!("Failed to open image file: {}", e))?
+    };
+
+    if image::guess_format(&bytes).ok() != Some(ImageFormat::Gif) {
+        let img = image::load_from_memory(&bytes)
+            .map_err(|e| format!("Failed to decode image: {}", e))?;
+        return Ok(Animation {
+            frames: vec![img],
+            speed_ms: 1000,
+        });
+    }
+
+    let decoder =
+        GifDecoder::new(Cursor::new(bytes)).map_err(|e| format!("Failed to decode GIF: {}", e))?;
+    let frames = decoder
+        .into_frames()
+        .take(divoom::MAX_FRAMES)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to decode GIF: {}", e))?;
+
+    // The device plays every frame at the same speed, so the first delay stands for all
+    let speed_ms = frames
+        .first()
+        .map(|f| {
+            let (numer, denom) = f.delay().numer_denom_ms();
+            numer / denom.max(1)
+        })
+        .filter(|ms| *ms > 0)
+        .unwrap_or(100);
+
+    Ok(Animation {
+        frames: frames
+            .into_iter()
+            .map(|f| DynamicImage::ImageRgba8(f.into_buffer()))
+            .collect(),
+        speed_ms,
+    })
+}
+
+pub async fn send_animation(
+    ip_address: &str,
+    screen_indexes: &[u32],
+    animation: &Animation,
+) -> Result<(), String> {
+    device_commands::check_lcds(ip_address, screen_indexes).await?;
+    device_commands::check_image_uploads(ip_address).await?;
+    let pic_id = pic_ids::allocate(ip_address, 1).await?;
+    divoom_api::client(ip_address)
+        .send_animation(
+            screen_indexes,
+            pic_id,
+            &animation.frames,
+            animation.speed_ms,
+        )
+        .await
+        .map_err(|e| format!("Failed to send animation: {}", e))
+}
+
+/// Cuts `img` into one tile per LCD, left to right.
+///
+/// The image is scaled to cover a strip of all LCDs plus `bezel_px` between each
+/// pair, and the part behind the bezels is dropped, so lines keep running straight
+/// across the gaps.
+pub fn panorama_tiles(img: &DynamicImage, bezel_px: u32) -> Vec<DynamicImage> {
+    let size = divoom::LCD_SIZE;
+    let count = divoom::LCD_COUNT as u32;
+    let width = size * count + bezel_px * (count - 1);
+    let strip = img.resize_to_fill(width, size, FilterType::Lanczos3);
+
+    (0..count)
+        .map(|i| strip.crop_imm(i * (size + bezel_px), 0, size, size))
+        .collect()
+}
+
+/// Spreads an image or animated GIF across all LCDs of a Times Gate.
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn upload_panorama(
+    ip_address: String,
+    source: String,
+    bezel_px: Option<u32>,
+) -> Result<(), String> {
+    let lcds: Vec<u32> = (0..divoom::LCD_COUNT as u32).collect();
+    device_commands::check_lcds(&ip_address, &lcds).await?;
+
+    let animation = load_animation(&source).await?;
+    let bezel_px = bezel_px.unwrap_or(0);
+
+    let mut tiles = vec![Vec::new(); divoom::LCD_COUNT];
+    for frame in &animation.frames {
+        for (tile, part) in tiles.iter_mut().zip(panorama_tiles(frame, bezel_px)) {
+            tile.push(part);
+        }
+    }
+
+    let first_pic_id = pic_ids::allocate(&ip_address, divoom::LCD_COUNT as u32).await?;
+    divoom_api::client(&ip_address)
+        .send_panorama(first_pic_id, &tiles, animation.speed_ms)
+        .await
+        .map_err(|e| format!("Failed to send panorama: {}", e))
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn upload_image_from_url(
+    ip_address: String,
+    screen_indexes: Vec<u32>,
+    url: String,
+) -> Result<(), String> {
+    let img = load_image_from_url(&url).await?;
+    send_image(&ip_address, &screen_indexes, img).await
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn upload_image_from_file(
+    ip_address: String,
+    screen_indexes: Vec<u32>,
+    file_path: String,
+) -> Result<(), String> {
+    let img = load_image_from_file(&file_path).await?;
+    send_image(&ip_address, &screen_indexes, img).await
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn set_screen_text(
+    ip_address: String,
+    screen_index: u32,
+    text_config: TextConfig,
+) -> Result<(), String> {
+    device_commands::check_lcds(&ip_address, &[screen_index]).await?;
+    device_commands::check_texts(&ip_address, [&text_config]).await?;
+
+    divoom_api::client(&ip_address)
+        .send_text(screen_index, &text_config)
+        .await
+        .map_err(|e| format!("Failed to send text command: {}", e))
+}
+
+/// Shows a whole text layout in one request; items other than plain text (time,
+/// date, temperature, ...) are rendered by the device.
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn set_screen_items(
+    ip_address: String,
+    screen_index: u32,
+    items: Vec<DisplayItem>,
+) -> Result<(), String> {
+    device_commands::check_lcds(&ip_address, &[screen_index]).await?;
+    device_commands::check_texts(&ip_address, items.iter().map(|item| &item.text)).await?;
+
+    divoom_api::client(&ip_address)
+        .send_items(screen_index, &items)
+        .await
+        .map_err(|e| format!("Failed to send text layout: {}", e))
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn clear_screen_text(ip_address: String, screen_index: u32) -> Result<(), String> {
+    divoom_api::client(&ip_address)
+        .clear_text(screen_index)
+        .await
+        .map_err(|e| format!("Failed to clear texts: {}", e))
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn get_lcd_info(ip_address: String) -> Result<LcdInfoResponse, String> {
+    let devices = discover_via_divoom_api().await?;
+
+    let device = devices
+        .iter()
+        .find(|d| d.ip_address.as_deref() == Some(&ip_address))
+        .ok_or_else(|| format!("Device with IP {} not found", ip_address))?;
+
+    let device_id = device.device_id.ok_or("Device has no ID")?;
+
+    divoom::cloud::lcd_info(device_id)
+        .await
+        .map_err(|e| format!("Failed to request LCD info: {}", e))
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn activate_pc_monitor(
+    ip_address: String,
+    device_id: u64,
+    lcd_independence: u64,
+    lcd_index: u32,
+) -> Result<(), String> {
+    divoom_api::client(&ip_address)
+        .select_clock(
+            device_id,
+            lcd_independence,
+            lcd_index,
+            divoom::PC_MONITOR_CLOCK_ID,
+        )
+        .await
+        .map_err(|e| format!("Failed to activate PC monitor: {}", e))
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn send_pc_metrics(
+    ip_address: String,
+    lcd_index: u32,
+    disp_data: Vec<String>,
+) -> Result<(), String> {
+    divoom_api::client(&ip_address)
+        .update_pc_monitor(lcd_index, &disp_data)
+        .await
+        .map_err(|e| format!("Failed to send PC metrics: {}", e))
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn send_system_metrics(ip_address: String, lcd_index: u32) -> Result<(), String> {
+    let metrics = metric_providers::collect_metrics().await;
+    send_metrics(ip_address, lcd_index, &metrics).await
+}
+
+/// Pushes already collected metrics through the device's pipelines.
+pub async fn send_metrics(
+    ip_address: String,
+    lcd_index: u32,
+    metrics: &SystemMetrics,
+) -> Result<(), String> {
+    let fahrenheit = device_commands::temperature_mode(&ip_address).await == 1;
+    let pipelines = metric_pipeline::device_pipelines(&ip_address, fahrenheit);
+    let consumer = metric_pipeline::lcd_consumer(&ip_address, lcd_index, "pc_monitor");
+    let processed = metric_pipeline::process(&consumer, metrics, &pipelines);
+
+    send_pc_metrics(
+        ip_address,
+        lcd_index,
+        metric_pipeline::pc_monitor_disp_data(&processed),
+    )
+    .await
+}
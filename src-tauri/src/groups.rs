@@ -0,0 +1,239 @@
+use std::collections::BTreeSet;
+use std::future::Future;
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::app_settings;
+use crate::divoom_api;
+use crate::draw_commands;
+use crate::metric_providers;
+use crate::models::TextConfig;
+use crate::runtime;
+
+/// Named set of devices driven together.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceGroup {
+    pub id: String,
+    pub name: String,
+    /// IP addresses of the member devices.
+    #[serde(default)]
+    pub devices: Vec<String>,
+}
+
+/// Outcome of a group command on one device.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceResult {
+    pub ip_address: String,
+    /// `None` when the command succeeded.
+    pub error: Option<String>,
+}
+
+/// Checks ids are unique and members are distinct IP addresses, so broken groups are
+/// rejected when saving.
+pub fn validate(groups: &[DeviceGroup]) -> Result<(), String> {
+    let mut ids = BTreeSet::new();
+    for group in groups {
+        if group.id.trim().is_empty() {
+            return Err(format!("Group '{}' has no id", group.name));
+        }
+        if !ids.insert(group.id.as_str()) {
+            return Err(format!("Group id '{}' is used twice", group.id));
+        }
+
+        let mut members = BTreeSet::new();
+        for ip in &group.devices {
+            let address: IpAddr = ip
+                .parse()
+                .map_err(|_| format!("Group '{}': '{}' is not an IP address", group.name, ip))?;
+            if !members.insert(address) {
+                return Err(format!("Group '{}': {} is listed twice", group.name, ip));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn group_devices(group_id: &str) -> Result<Vec<String>, String> {
+    let group = app_settings::device_groups()
+        .into_iter()
+        .find(|g| g.id == group_id)
+        .ok_or_else(|| format!("Group '{}' not found", group_id))?;
+
+    if group.devices.is_empty() {
+        return Err(format!("Group '{}' has no devices", group.name));
+    }
+    Ok(group.devices)
+}
+
+/// Runs `action` on every device of the group at once and collects the results
+/// in the group's order.
+async fn fan_out<F, Fut>(group_id: &str, action: F) -> Result<Vec<DeviceResult>, String>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<(), String>> + Send + 'static,
+{
+    Ok(run_on(group_devices(group_id)?, action).await)
+}
+
+async fn run_on<F, Fut>(devices: Vec<String>, action: F) -> Vec<DeviceResult>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<(), String>> + Send + 'static,
+{
+    let handles: Vec<_> = devices
+        .into_iter()
+        .map(|ip| (ip.clone(), runtime::spawn(action(ip))))
+        .collect();
+
+    let mut results = Vec::with_capacity(handles.len());
+    for (ip_address, handle) in handles {
+        let error = match handle.await {
+            Ok(result) => result.err(),
+            Err(e) => Some(format!("Command task failed: {}", e)),
+        };
+        results.push(DeviceResult { ip_address, error });
+    }
+    results
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn group_set_brightness(
+    group_id: String,
+    value: u8,
+) -> Result<Vec<DeviceResult>, String> {
+    fan_out(&group_id, |ip| async move {
+        divoom_api::client(&ip)
+            .set_brightness(value)
+            .await
+            .map_err(|e| format!("Failed to set brightness: {}", e))
+    })
+    .await
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn group_set_screen(group_id: String, on: bool) -> Result<Vec<DeviceResult>, String> {
+    fan_out(&group_id, |ip| async move {
+        divoom_api::client(&ip)
+            .set_screen_on(on)
+            .await
+            .map_err(|e| format!("Failed to switch screen: {}", e))
+    })
+    .await
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn group_set_text(
+    group_id: String,
+    screen_index: u32,
+    text_config: TextConfig,
+) -> Result<Vec<DeviceResult>, String> {
+    fan_out(&group_id, |ip| {
+        let text_config = text_config.clone();
+        async move { draw_commands::set_screen_text(ip, screen_index, text_config).await }
+    })
+    .await
+}
+
+/// Loads the image once and sends it to every device; `source` is an http(s) URL
+/// or a local file path.
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn group_upload_image(
+    group_id: String,
+    screen_indexes: Vec<u32>,
+    source: String,
+) -> Result<Vec<DeviceResult>, String> {
+    let animation = Arc::new(draw_commands::load_animation(&source).await?);
+
+    fan_out(&group_id, |ip| {
+        let animation = Arc::clone(&animation);
+        let screen_indexes = screen_indexes.clone();
+        async move { draw_commands::send_animation(&ip, &screen_indexes, &animation).await }
+    })
+    .await
+}
+
+/// Collects metrics once and pushes them to every device of the group.
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn group_send_system_metrics(
+    group_id: String,
+    lcd_index: u32,
+) -> Result<Vec<DeviceResult>, String> {
+    let metrics = Arc::new(metric_providers::collect_metrics().await);
+
+    fan_out(&group_id, |ip| {
+        let metrics = Arc::clone(&metrics);
+        async move { draw_commands::send_metrics(ip, lcd_index, &metrics).await }
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn group(id: &str, devices: &[&str]) -> DeviceGroup {
+        DeviceGroup {
+            id: id.to_string(),
+            name: id.to_uppercase(),
+            devices: devices.iter().map(|ip| ip.to_string()).collect(),
+        }
+    }
+
+    #[tokio::test]
+    async fn results_keep_the_group_order() {
+        let devices = vec![
+            "10.0.0.1".to_string(),
+            "10.0.0.2".to_string(),
+            "10.0.0.3".to_string(),
+        ];
+
+        // The first device answers last and the second one fails
+        let results = run_on(devices.clone(), |ip| async move {
+            let delay = if ip.ends_with(".1") { 50 } else { 0 };
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+            if ip.ends_with(".2") {
+                Err(format!("{} is offline", ip))
+            } else {
+                Ok(())
+            }
+        })
+        .await;
+
+        let order: Vec<&str> = results.iter().map(|r| r.ip_address.as_str()).collect();
+        assert_eq!(order, devices);
+        let errors: Vec<Option<&str>> = results.iter().map(|r| r.error.as_deref()).collect();
+        assert_eq!(errors, vec![None, Some("10.0.0.2 is offline"), None]);
+    }
+
+    #[test]
+    fn unknown_and_empty_groups_are_errors() {
+        app_settings::set_device_groups(vec![
+            group("office", &["10.0.0.1", "10.0.0.2"]),
+            group("empty", &[]),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            group_devices("office").unwrap(),
+            vec!["10.0.0.1", "10.0.0.2"]
+        );
+        assert_eq!(
+            group_devices("empty").unwrap_err(),
+            "Group 'EMPTY' has no devices"
+        );
+        assert_eq!(group_devices("lab").unwrap_err(), "Group 'lab' not found");
+    }
+
+    #[test]
+    fn broken_groups_are_rejected() {
+        assert!(validate(&[group("office", &["10.0.0.1", "fe80::1"])]).is_ok());
+        assert!(validate(&[group("", &["10.0.0.1"])]).is_err());
+        assert!(validate(&[group("a", &[]), group("a", &[])]).is_err());
+        assert!(validate(&[group("office", &["pixoo.local"])]).is_err());
+        assert!(validate(&[group("office", &["10.0.0.1", "10.0.0.1"])]).is_err());
+    }
+}
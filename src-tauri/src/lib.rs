@@ -1,138 +1,273 @@
-mod app_settings;
-mod device_commands;
-mod divoom_api;
-mod draw_commands;
-mod models;
-mod system_metrics;
-
-use std::sync::atomic::Ordering;
-use tauri::Manager;
-use tauri::menu::{MenuBuilder, MenuItemBuilder};
-use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
-
-#[cfg(debug_assertions)]
-fn setup_devtools(app: &tauri::App) {
-    if let Some(main_window) = app.get_webview_window("main") {
-        main_window.open_devtools();
-    }
-}
-
-#[cfg(not(debug_assertions))]
-fn setup_devtools(_app: &tauri::App) {}
-
-#[cfg_attr(mobile, tauri::mobile_entry_point)]
-pub fn run() {
-    dotenvy::dotenv().ok();
-
-    let _ = std::panic::set_hook(Box::new(|_| {
-        system_metrics::stop_sidecar_service();
-    }));
-
-    tauri::Builder::default()
-        .setup(|app| {
-            setup_devtools(app);
-
-            if let Some(data_dir) = app.path().app_data_dir().ok() {
-                app_settings::init(data_dir);
-            }
-
-            system_metrics::setup_sidecar_service();
-
-            let show_item = MenuItemBuilder::with_id("show", "Показать").build(app)?;
-            let quit_item = MenuItemBuilder::with_id("quit", "Выход").build(app)?;
-            let tray_menu = MenuBuilder::new(app)
-                .items(&[&show_item, &quit_item])
-                .build()?;
-
-            let _tray = TrayIconBuilder::new()
-                .icon(app.default_window_icon().unwrap().clone())
-                .tooltip("Divoom Monitor")
-                .menu(&tray_menu)
-                .show_menu_on_left_click(false)
-                .on_menu_event(|app, event| match event.id().as_ref() {
-                    "show" => {
-                        if let Some(window) = app.get_webview_window("main") {
-                            let _ = window.unminimize();
-                            let _ = window.show();
-                            let _ = window.set_focus();
-                        }
-                    }
-                    "quit" => {
-                        system_metrics::stop_sidecar_service();
-                        app.exit(0);
-                    }
-                    _ => {}
-                })
-                .on_tray_icon_event(|tray, event| {
-                    if let TrayIconEvent::Click {
-                        button: MouseButton::Left,
-                        button_state: MouseButtonState::Up,
-                        ..
-                    } = event
-                    {
-                        let app = tray.app_handle();
-                        if let Some(window) = app.get_webview_window("main") {
-                            let _ = window.unminimize();
-                            let _ = window.show();
-                            let _ = window.set_focus();
-                        }
-                    }
-                })
-                .build(app)?;
-
-            if let Some(window) = app.get_webview_window("main") {
-                let window_clone = window.clone();
-                let app_handle = app.handle().clone();
-                let _ = window.on_window_event(move |event| {
-                    if let tauri::WindowEvent::CloseRequested { api, .. } = event {
-                        if app_settings::CLOSE_TO_TRAY.load(Ordering::Relaxed) {
-                            api.prevent_close();
-                            let _ = window_clone.hide();
-                        } else {
-                            system_metrics::stop_sidecar_service();
-                            app_handle.exit(0);
-                        }
-                    }
-                });
-
-                let args: Vec<String> = std::env::args().collect();
-                if args.iter().any(|a| a == "--minimized") {
-                    let _ = window.hide();
-                }
-            }
-
-            Ok(())
-        })
-        .plugin(tauri_plugin_opener::init())
-        .plugin(tauri_plugin_dialog::init())
-        .plugin(tauri_plugin_fs::init())
-        .plugin(tauri_plugin_updater::Builder::new().build())
-        .plugin(tauri_plugin_process::init())
-        .plugin(tauri_plugin_autostart::init(
-            tauri_plugin_autostart::MacosLauncher::LaunchAgent,
-            Some(vec!["--minimized"]),
-        ))
-        .invoke_handler(tauri::generate_handler![
-            device_commands::scan_devices,
-            device_commands::get_device_info,
-            device_commands::set_brightness,
-            device_commands::set_switch_screen,
-            device_commands::set_temperature_mode,
-            device_commands::set_mirror_mode,
-            device_commands::set_24_hours_mode,
-            device_commands::reboot_device,
-            draw_commands::upload_image_from_url,
-            draw_commands::upload_image_from_file,
-            draw_commands::set_screen_text,
-            draw_commands::get_lcd_info,
-            draw_commands::activate_pc_monitor,
-            draw_commands::send_pc_metrics,
-            system_metrics::get_system_metrics,
-            app_settings::set_close_to_tray,
-            app_settings::get_close_to_tray,
-        ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
-
-    system_metrics::stop_sidecar_service();
-}
+pub mod alerts;
+pub mod app_settings;
+pub mod automation;
+pub mod brightness_schedule;
+pub mod channels;
+pub mod device_commands;
+pub mod divoom_api;
+pub mod draw_commands;
+pub mod groups;
+pub mod metric_pipeline;
+pub mod metric_providers;
+pub mod models;
+pub mod pic_ids;
+pub mod playlists;
+pub mod prometheus_exporter;
+pub mod rest_api;
+mod runtime;
+pub mod scenes;
+pub mod system_metrics;
+pub mod time_sync;
+pub mod tools;
+pub mod weather;
+
+#[cfg(feature = "gui")]
+use std::sync::atomic::Ordering;
+#[cfg(feature = "gui")]
+use tauri::menu::{MenuBuilder, MenuItemBuilder};
+#[cfg(feature = "gui")]
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+#[cfg(feature = "gui")]
+use tauri::{Emitter, Manager};
+
+#[cfg(all(feature = "gui", debug_assertions))]
+fn setup_devtools(app: &tauri::App) {
+    if let Some(main_window) = app.get_webview_window("main") {
+        main_window.open_devtools();
+    }
+}
+
+#[cfg(all(feature = "gui", not(debug_assertions)))]
+fn setup_devtools(_app: &tauri::App) {}
+
+#[cfg(feature = "gui")]
+#[cfg_attr(mobile, tauri::mobile_entry_point)]
+pub fn run() {
+    dotenvy::dotenv().ok();
+
+    let _ = std::panic::set_hook(Box::new(|_| {
+        system_metrics::stop_sidecar_service();
+    }));
+
+    tauri::Builder::default()
+        .setup(|app| {
+            setup_devtools(app);
+
+            if let Some(data_dir) = app.path().app_data_dir().ok() {
+                app_settings::init(data_dir);
+            }
+
+            system_metrics::register_builtin_providers();
+            let handle = app.handle().clone();
+            alerts::start(Some(Box::new(move |event| {
+                let _ = handle.emit("metric-alert", event.clone());
+            })));
+            runtime::spawn(async {
+                let settings = app_settings::prometheus_settings();
+                if let Err(e) = prometheus_exporter::apply(settings).await {
+                    eprintln!("[Prometheus] {}", e);
+                }
+            });
+            runtime::spawn(async {
+                if let Err(e) = rest_api::apply(app_settings::rest_api_settings()).await {
+                    eprintln!("[REST API] {}", e);
+                }
+            });
+            time_sync::start();
+            weather::start();
+            brightness_schedule::start();
+            automation::start();
+            playlists::start();
+
+            system_metrics::setup_sidecar_service();
+
+            let show_item = MenuItemBuilder::with_id("show", "Показать").build(app)?;
+            let clock_item = MenuItemBuilder::with_id("clock_face", "Циферблат").build(app)?;
+            let pc_monitor_item =
+                MenuItemBuilder::with_id("pc_monitor", "PC Monitor").build(app)?;
+            let quit_item = MenuItemBuilder::with_id("quit", "Выход").build(app)?;
+            let tray_menu = MenuBuilder::new(app)
+                .items(&[&show_item, &clock_item, &pc_monitor_item, &quit_item])
+                .build()?;
+
+            let _tray = TrayIconBuilder::new()
+                .icon(app.default_window_icon().unwrap().clone())
+                .tooltip("Divoom Monitor")
+                .menu(&tray_menu)
+                .show_menu_on_left_click(false)
+                .on_menu_event(|app, event| match event.id().as_ref() {
+                    "show" => {
+                        if let Some(window) = app.get_webview_window("main") {
+                            let _ = window.unminimize();
+                            let _ = window.show();
+                            let _ = window.set_focus();
+                        }
+                    }
+                    "clock_face" => {
+                        runtime::spawn(async {
+                            if let Err(e) = channels::show_clock_face().await {
+                                eprintln!("[Tray] {}", e);
+                            }
+                        });
+                    }
+                    "pc_monitor" => {
+                        runtime::spawn(async {
+                            if let Err(e) = channels::show_pc_monitor().await {
+                                eprintln!("[Tray] {}", e);
+                            }
+                        });
+                    }
+                    "quit" => {
+                        system_metrics::stop_sidecar_service();
+                        app.exit(0);
+                    }
+                    _ => {}
+                })
+                .on_tray_icon_event(|tray, event| {
+                    if let TrayIconEvent::Click {
+                        button: MouseButton::Left,
+                        button_state: MouseButtonState::Up,
+                        ..
+                    } = event
+                    {
+                        let app = tray.app_handle();
+                        if let Some(window) = app.get_webview_window("main") {
+                            let _ = window.unminimize();
+                            let _ = window.show();
+                            let _ = window.set_focus();
+                        }
+                    }
+                })
+                .build(app)?;
+
+            if let Some(window) = app.get_webview_window("main") {
+                let window_clone = window.clone();
+                let app_handle = app.handle().clone();
+                let _ = window.on_window_event(move |event| {
+                    if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                        if app_settings::CLOSE_TO_TRAY.load(Ordering::Relaxed) {
+                            api.prevent_close();
+                            let _ = window_clone.hide();
+                        } else {
+                            system_metrics::stop_sidecar_service();
+                            app_handle.exit(0);
+                        }
+                    }
+                });
+
+                let args: Vec<String> = std::env::args().collect();
+                if args.iter().any(|a| a == "--minimized") {
+                    let _ = window.hide();
+                }
+            }
+
+            Ok(())
+        })
+        .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_fs::init())
+        .plugin(tauri_plugin_updater::Builder::new().build())
+        .plugin(tauri_plugin_process::init())
+        .plugin(tauri_plugin_autostart::init(
+            tauri_plugin_autostart::MacosLauncher::LaunchAgent,
+            Some(vec!["--minimized"]),
+        ))
+        .invoke_handler(tauri::generate_handler![
+            device_commands::scan_devices,
+            device_commands::get_device_info,
+            device_commands::set_brightness,
+            device_commands::set_switch_screen,
+            device_commands::set_temperature_mode,
+            device_commands::set_mirror_mode,
+            device_commands::set_24_hours_mode,
+            channels::set_channel,
+            channels::get_channel_selection,
+            channels::set_custom_page,
+            channels::set_visualizer,
+            channels::set_cloud_channel,
+            channels::select_clock_face,
+            channels::get_clock_face_types,
+            channels::get_clock_faces,
+            device_commands::play_buzzer,
+            device_commands::play_buzzer_pattern,
+            device_commands::reboot_device,
+            draw_commands::upload_image_from_url,
+            draw_commands::upload_image_from_file,
+            draw_commands::upload_panorama,
+            pic_ids::reset_pic_id,
+            draw_commands::set_screen_text,
+            draw_commands::set_screen_items,
+            draw_commands::clear_screen_text,
+            draw_commands::get_lcd_info,
+            draw_commands::activate_pc_monitor,
+            draw_commands::send_pc_metrics,
+            draw_commands::send_system_metrics,
+            system_metrics::get_system_metrics,
+            metric_pipeline::get_processed_metrics,
+            time_sync::get_host_time_zone,
+            time_sync::set_device_time_zone,
+            time_sync::set_device_utc,
+            time_sync::sync_device_time,
+            tools::get_tool_state,
+            tools::start_timer,
+            tools::stop_timer,
+            tools::control_stopwatch,
+            tools::set_scoreboard,
+            tools::change_score,
+            tools::set_noise_meter,
+            weather::set_device_location,
+            weather::get_device_weather,
+            brightness_schedule::get_sun_times,
+            automation::run_automation_job,
+            automation::get_automation_history,
+            playlists::set_playlist_paused,
+            playlists::skip_playlist_entry,
+            playlists::get_playlist_status,
+            scenes::list_scenes,
+            scenes::get_scene,
+            scenes::save_scene,
+            scenes::delete_scene,
+            scenes::capture_scene,
+            scenes::apply_scene,
+            groups::group_set_brightness,
+            groups::group_set_screen,
+            groups::group_set_text,
+            groups::group_upload_image,
+            groups::group_send_system_metrics,
+            alerts::test_alert_rule,
+            metric_providers::get_sensor_catalog,
+            metric_providers::test_metric_provider,
+            app_settings::set_close_to_tray,
+            app_settings::get_close_to_tray,
+            app_settings::set_metric_providers,
+            app_settings::get_metric_providers,
+            app_settings::set_sensor_mapping,
+            app_settings::get_sensor_mapping,
+            app_settings::set_metric_pipelines,
+            app_settings::get_metric_pipelines,
+            app_settings::set_alert_rules,
+            app_settings::get_alert_rules,
+            app_settings::set_prometheus_settings,
+            app_settings::get_prometheus_settings,
+            app_settings::set_rest_api_settings,
+            app_settings::get_rest_api_settings,
+            app_settings::set_time_sync_settings,
+            app_settings::get_time_sync_settings,
+            app_settings::set_weather_settings,
+            app_settings::get_weather_settings,
+            app_settings::set_tray_channel_settings,
+            app_settings::get_tray_channel_settings,
+            app_settings::set_brightness_schedule,
+            app_settings::get_brightness_schedule,
+            app_settings::set_automation_jobs,
+            app_settings::get_automation_jobs,
+            app_settings::set_playlists,
+            app_settings::get_playlists,
+            app_settings::set_device_groups,
+            app_settings::get_device_groups,
+        ])
+        .run(tauri::generate_context!())
+        .expect("error while running tauri application");
+
+    system_metrics::stop_sidecar_service();
+}
@@ -0,0 +1,370 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::app_settings;
+use crate::metric_providers;
+use crate::system_metrics::SystemMetrics;
+
+pub const TARGET_UI: &str = "ui";
+pub const TARGET_DEVICE: &str = "device";
+pub const TARGET_ALERTS: &str = "alerts";
+
+pub const MEMORY_USAGE: &str = "memory_usage";
+pub const DISK_USAGE: &str = "disk_usage";
+
+static SMOOTHING_STATE: Mutex<BTreeMap<String, SmoothingState>> = Mutex::new(BTreeMap::new());
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Smoothing {
+    /// Exponential moving average, `alpha` in (0, 1]; lower is smoother.
+    Ema { alpha: f64 },
+    /// Median of the last `window` samples.
+    Median { window: usize },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnitConversion {
+    CelsiusToFahrenheit,
+    BytesToGb,
+    BytesToGib,
+}
+
+impl UnitConversion {
+    fn apply(self, value: f64) -> f64 {
+        match self {
+            UnitConversion::CelsiusToFahrenheit => value * 9.0 / 5.0 + 32.0,
+            UnitConversion::BytesToGb => value / 1_000_000_000.0,
+            UnitConversion::BytesToGib => value / 1_073_741_824.0,
+        }
+    }
+
+    fn unit(self) -> &'static str {
+        match self {
+            UnitConversion::CelsiusToFahrenheit => "F",
+            UnitConversion::BytesToGb => "GB",
+            UnitConversion::BytesToGib => "GiB",
+        }
+    }
+}
+
+/// Post-processing applied to one metric, in order: smoothing, conversion,
+/// clamping, rounding.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MetricPipeline {
+    #[serde(default)]
+    pub smoothing: Option<Smoothing>,
+    #[serde(default)]
+    pub conversion: Option<UnitConversion>,
+    #[serde(default)]
+    pub min: Option<f64>,
+    #[serde(default)]
+    pub max: Option<f64>,
+    #[serde(default)]
+    pub precision: Option<u8>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessedValue {
+    pub value: f64,
+    pub unit: String,
+    pub text: String,
+}
+
+enum SmoothingState {
+    Ema(f64),
+    Median(VecDeque<f64>),
+}
+
+fn smooth(key: &str, smoothing: Smoothing, value: f64) -> f64 {
+    let Ok(mut states) = SMOOTHING_STATE.lock() else {
+        return value;
+    };
+
+    match smoothing {
+        Smoothing::Ema { alpha } => {
+            let alpha = alpha.clamp(0.01, 1.0);
+            let next = match states.get(key) {
+                Some(SmoothingState::Ema(prev)) => prev + alpha * (value - prev),
+                _ => value,
+            };
+            states.insert(key.to_string(), SmoothingState::Ema(next));
+            next
+        }
+        Smoothing::Median { window } => {
+            let window = window.max(1);
+            if !matches!(states.get(key), Some(SmoothingState::Median(_))) {
+                states.insert(key.to_string(), SmoothingState::Median(VecDeque::new()));
+            }
+            let Some(SmoothingState::Median(samples)) = states.get_mut(key) else {
+                return value;
+            };
+
+            samples.push_back(value);
+            while samples.len() > window {
+                samples.pop_front();
+            }
+
+            let mut sorted: Vec<f64> = samples.iter().copied().collect();
+            sorted.sort_by(|a, b| a.total_cmp(b));
+            let mid = sorted.len() / 2;
+            if sorted.len() % 2 == 0 {
+                (sorted[mid - 1] + sorted[mid]) / 2.0
+            } else {
+                sorted[mid]
+            }
+        }
+    }
+}
+
+pub fn reset_smoothing() {
+    if let Ok(mut states) = SMOOTHING_STATE.lock() {
+        states.clear();
+    }
+}
+
+/// Flattens metrics into named raw values with their base units.
+fn raw_values(metrics: &SystemMetrics) -> Vec<(String, f64, &'static str)> {
+    let mut values = vec![(
+        metric_providers::CPU_USAGE.to_string(),
+        metrics.cpu_usage as f64,
+        "%",
+    )];
+
+    if let Some(v) = metrics.cpu_temperature {
+        values.push((metric_providers::CPU_TEMPERATURE.to_string(), v as f64, "C"));
+    }
+    if let Some(v) = metrics.gpu_usage {
+        values.push((metric_providers::GPU_USAGE.to_string(), v as f64, "%"));
+    }
+    if let Some(v) = metrics.gpu_temperature {
+        values.push((metric_providers::GPU_TEMPERATURE.to_string(), v as f64, "C"));
+    }
+
+    values.push((
+        metric_providers::MEMORY_TOTAL.to_string(),
+        metrics.memory_total as f64,
+        "B",
+    ));
+    values.push((
+        metric_providers::MEMORY_USED.to_string(),
+        metrics.memory_used as f64,
+        "B",
+    ));
+
+    let memory_usage = if metrics.memory_total > 0 {
+        metrics.memory_used as f64 / metrics.memory_total as f64 * 100.0
+    } else {
+        0.0
+    };
+    values.push((MEMORY_USAGE.to_string(), memory_usage, "%"));
+
+    let disk_usage = metrics
+        .disks
+        .iter()
+        .map(|d| d.usage_percent as f64)
+        .reduce(f64::max)
+        .unwrap_or(0.0);
+    values.push((DISK_USAGE.to_string(), disk_usage, "%"));
+
+    for (key, value) in &metrics.custom {
+        values.push((key.clone(), *value, ""));
+    }
+
+    values
+}
+
+/// Runs every metric through `pipelines`. Metrics without a pipeline are passed
+/// through unchanged.
+///
+/// Smoothing state is kept per `consumer`, so each consumer has to be unique to one
+/// polling loop (e.g. one LCD); two loops sharing it would both advance the window.
+pub fn process(
+    consumer: &str,
+    metrics: &SystemMetrics,
+    pipelines: &BTreeMap<String, MetricPipeline>,
+) -> BTreeMap<String, ProcessedValue> {
+    let mut processed = BTreeMap::new();
+
+    for (name, raw, base_unit) in raw_values(metrics) {
+        let pipeline = pipelines.get(&name).cloned().unwrap_or_default();
+        let mut value = raw;
+        let mut unit = base_unit.to_string();
+
+        if let Some(smoothing) = pipeline.smoothing {
+            value = smooth(&format!("{}:{}", consumer, name), smoothing, value);
+        }
+        if let Some(conversion) = pipeline.conversion {
+            value = conversion.apply(value);
+            unit = conversion.unit().to_string();
+        }
+        if let Some(min) = pipeline.min {
+            value = value.max(min);
+        }
+        if let Some(max) = pipeline.max {
+            value = value.min(max);
+        }
+
+        let text = match pipeline.precision {
+            Some(precision) => {
+                let factor = 10f64.powi(precision as i32);
+                value = (value * factor).round() / factor;
+                format!("{:.*}", precision as usize, value)
+            }
+            None => value.to_string(),
+        };
+
+        processed.insert(name, ProcessedValue { value, unit, text });
+    }
+
+    processed
+}
+
+/// Smoothing key of a metrics loop drawing on one LCD of a device.
+pub fn lcd_consumer(ip: &str, lcd_index: u32, what: &str) -> String {
+    format!("{}/{}/{}", ip, lcd_index, what)
+}
+
+/// Pipelines for a target; a device IP falls back to the shared `device` target.
+pub fn pipelines_for(target: &str) -> BTreeMap<String, MetricPipeline> {
+    let all = app_settings::metric_pipelines();
+    all.get(target)
+        .or_else(|| {
+            if target == TARGET_UI || target == TARGET_ALERTS {
+                None
+            } else {
+                all.get(TARGET_DEVICE)
+            }
+        })
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Pipelines for a PC monitor push. Displayed values are rounded to whole numbers
+/// and temperatures follow the device's unit unless configured otherwise.
+pub fn device_pipelines(ip: &str, fahrenheit: bool) -> BTreeMap<String, MetricPipeline> {
+    let mut pipelines = pipelines_for(ip);
+
+    for metric in [
+        metric_providers::CPU_USAGE,
+        metric_providers::GPU_USAGE,
+        metric_providers::CPU_TEMPERATURE,
+        metric_providers::GPU_TEMPERATURE,
+        MEMORY_USAGE,
+        DISK_USAGE,
+    ] {
+        let pipeline = pipelines.entry(metric.to_string()).or_default();
+        if pipeline.precision.is_none() {
+            pipeline.precision = Some(0);
+        }
+    }
+
+    if fahrenheit {
+        for metric in [
+            metric_providers::CPU_TEMPERATURE,
+            metric_providers::GPU_TEMPERATURE,
+        ] {
+            let pipeline = pipelines.entry(metric.to_string()).or_default();
+            if pipeline.conversion.is_none() {
+                pipeline.conversion = Some(UnitConversion::CelsiusToFahrenheit);
+            }
+        }
+    }
+
+    pipelines
+}
+
+/// Builds the six `DispData` strings of the PC monitor clock face:
+/// CPU load, GPU load, CPU temp, GPU temp, RAM and disk usage.
+pub fn pc_monitor_disp_data(processed: &BTreeMap<String, ProcessedValue>) -> Vec<String> {
+    let percent = |metric: &str| {
+        processed
+            .get(metric)
+            .map(|v| format!("{}%", v.text))
+            .unwrap_or_else(|| "0%".to_string())
+    };
+    let temperature = |metric: &str| {
+        processed
+            .get(metric)
+            .map(|v| format!("{} {}", v.text, v.unit))
+            .unwrap_or_else(|| "N/A".to_string())
+    };
+
+    vec![
+        percent(metric_providers::CPU_USAGE),
+        percent(metric_providers::GPU_USAGE),
+        temperature(metric_providers::CPU_TEMPERATURE),
+        temperature(metric_providers::GPU_TEMPERATURE),
+        percent(MEMORY_USAGE),
+        percent(DISK_USAGE),
+    ]
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn get_processed_metrics(
+    target: String,
+) -> Result<BTreeMap<String, ProcessedValue>, String> {
+    let metrics = metric_providers::collect_metrics().await;
+    Ok(process(&target, &metrics, &pipelines_for(&target)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics(cpu_usage: f32) -> SystemMetrics {
+        SystemMetrics {
+            cpu_usage,
+            cpu_temperature: None,
+            gpu_usage: None,
+            gpu_temperature: None,
+            memory_total: 0,
+            memory_used: 0,
+            disks: Vec::new(),
+            custom: BTreeMap::new(),
+        }
+    }
+
+    fn cpu(consumer: &str, pipelines: &BTreeMap<String, MetricPipeline>, usage: f32) -> f64 {
+        process(consumer, &metrics(usage), pipelines)[metric_providers::CPU_USAGE].value
+    }
+
+    fn pipelines(smoothing: Smoothing) -> BTreeMap<String, MetricPipeline> {
+        BTreeMap::from([(
+            metric_providers::CPU_USAGE.to_string(),
+            MetricPipeline {
+                smoothing: Some(smoothing),
+                ..Default::default()
+            },
+        )])
+    }
+
+    #[test]
+    fn consumers_keep_their_own_smoothing_state() {
+        let pipelines = pipelines(Smoothing::Ema { alpha: 0.5 });
+        let first = lcd_consumer("10.0.0.1", 0, "pc_monitor");
+        let second = lcd_consumer("10.0.0.1", 1, "pc_monitor");
+
+        assert_eq!(cpu(&first, &pipelines, 0.0), 0.0);
+        assert_eq!(cpu(&first, &pipelines, 100.0), 50.0);
+        // A second LCD starts from its own first sample
+        assert_eq!(cpu(&second, &pipelines, 100.0), 100.0);
+        assert_eq!(cpu(&first, &pipelines, 100.0), 75.0);
+    }
+
+    #[test]
+    fn median_averages_the_middle_of_an_even_window() {
+        let pipelines = pipelines(Smoothing::Median { window: 4 });
+        let consumer = "test-median";
+
+        assert_eq!(cpu(consumer, &pipelines, 10.0), 10.0);
+        assert_eq!(cpu(consumer, &pipelines, 30.0), 20.0);
+        assert_eq!(cpu(consumer, &pipelines, 20.0), 20.0);
+        assert_eq!(cpu(consumer, &pipelines, 90.0), 25.0);
+        // The first sample has left the window
+        assert_eq!(cpu(consumer, &pipelines, 40.0), 35.0);
+    }
+}
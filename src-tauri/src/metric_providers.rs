@@ -0,0 +1,493 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::app_settings;
+use crate::runtime;
+use crate::system_metrics::{DiskUsage, SystemMetrics};
+
+pub const CPU_USAGE: &str = "cpu_usage";
+pub const CPU_TEMPERATURE: &str = "cpu_temperature";
+pub const GPU_USAGE: &str = "gpu_usage";
+pub const GPU_TEMPERATURE: &str = "gpu_temperature";
+pub const MEMORY_TOTAL: &str = "memory_total";
+pub const MEMORY_USED: &str = "memory_used";
+pub const DISKS: &str = "disks";
+
+static PROVIDERS: RwLock<Vec<Arc<dyn MetricProvider>>> = RwLock::new(Vec::new());
+
+/// A single sensor discovered by a provider. `id` is stable across restarts and
+/// always starts with the id of the provider that reported it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SensorInfo {
+    pub id: String,
+    pub label: String,
+    pub kind: String,
+    pub unit: String,
+    pub value: Option<f64>,
+    pub provider: String,
+}
+
+/// Partial set of metrics reported by a single provider.
+#[derive(Debug, Clone, Default)]
+pub struct MetricSample {
+    pub cpu_usage: Option<f32>,
+    pub cpu_temperature: Option<f32>,
+    pub gpu_usage: Option<f32>,
+    pub gpu_temperature: Option<f32>,
+    pub memory_total: Option<u64>,
+    pub memory_used: Option<u64>,
+    pub disks: Option<Vec<DiskUsage>>,
+    pub custom: BTreeMap<String, f64>,
+    pub sensors: Vec<SensorInfo>,
+}
+
+impl MetricSample {
+    /// Stores a single numeric value under a built-in field or a custom key.
+    /// `disks` is a list and cannot be set this way; [`validate`] rejects it.
+    pub fn set_value(&mut self, metric: &str, value: f64) {
+        match metric {
+            CPU_USAGE => self.cpu_usage = Some(value as f32),
+            CPU_TEMPERATURE => self.cpu_temperature = Some(value as f32),
+            GPU_USAGE => self.gpu_usage = Some(value as f32),
+            GPU_TEMPERATURE => self.gpu_temperature = Some(value as f32),
+            MEMORY_TOTAL => self.memory_total = Some(value.max(0.0) as u64),
+            MEMORY_USED => self.memory_used = Some(value.max(0.0) as u64),
+            DISKS => {}
+            custom => {
+                self.custom.insert(custom.to_string(), value);
+            }
+        }
+    }
+
+    /// Fills every field that is still empty with the value from `other`.
+    pub fn merge_missing(&mut self, other: MetricSample) {
+        self.cpu_usage = self.cpu_usage.or(other.cpu_usage);
+        self.cpu_temperature = self.cpu_temperature.or(other.cpu_temperature);
+        self.gpu_usage = self.gpu_usage.or(other.gpu_usage);
+        self.gpu_temperature = self.gpu_temperature.or(other.gpu_temperature);
+        self.memory_total = self.memory_total.or(other.memory_total);
+        self.memory_used = self.memory_used.or(other.memory_used);
+        if self.disks.is_none() {
+            self.disks = other.disks;
+        }
+        for (key, value) in other.custom {
+            self.custom.entry(key).or_insert(value);
+        }
+        self.sensors.extend(other.sensors);
+    }
+
+    /// Overrides slots with the values of the sensors the user pinned to them.
+    pub fn apply_sensor_mapping(&mut self, mapping: &BTreeMap<String, String>) {
+        for (slot, sensor_id) in mapping {
+            let value = self
+                .sensors
+                .iter()
+                .find(|sensor| &sensor.id == sensor_id)
+                .and_then(|sensor| sensor.value);
+
+            if let Some(value) = value {
+                self.set_value(slot, value);
+            }
+        }
+    }
+
+    pub fn into_metrics(self) -> SystemMetrics {
+        SystemMetrics {
+            cpu_usage: self.cpu_usage.unwrap_or(0.0),
+            cpu_temperature: self.cpu_temperature,
+            gpu_usage: self.gpu_usage,
+            gpu_temperature: self.gpu_temperature,
+            memory_total: self.memory_total.unwrap_or(0),
+            memory_used: self.memory_used.unwrap_or(0),
+            disks: self.disks.unwrap_or_default(),
+            custom: self.custom,
+        }
+    }
+}
+
+/// A source of metric values. Providers with a higher priority win on a
+/// per-field basis; lower ones only fill in what is still missing.
+#[async_trait]
+pub trait MetricProvider: Send + Sync {
+    fn id(&self) -> String;
+
+    fn priority(&self) -> i32;
+
+    async fn collect(&self) -> Result<MetricSample, String>;
+}
+
+pub fn register_provider(provider: Arc<dyn MetricProvider>) {
+    if let Ok(mut providers) = PROVIDERS.write() {
+        let id = provider.id();
+        providers.retain(|p| p.id() != id);
+        providers.push(provider);
+    }
+}
+
+fn active_providers() -> Vec<Arc<dyn MetricProvider>> {
+    let mut providers: Vec<Arc<dyn MetricProvider>> =
+        PROVIDERS.read().map(|p| p.clone()).unwrap_or_default();
+
+    for config in app_settings::metric_providers() {
+        if config.enabled {
+            providers.push(Arc::new(ConfiguredProvider { config }));
+        }
+    }
+
+    providers.sort_by_key(|p| std::cmp::Reverse(p.priority()));
+    providers
+}
+
+/// Runs every provider at once, so a slow command or endpoint only holds up itself,
+/// and returns the samples of those that answered, highest priority first.
+async fn collect_all() -> Vec<MetricSample> {
+    let handles: Vec<_> = active_providers()
+        .into_iter()
+        .map(|provider| runtime::spawn(async move { provider.collect().await }))
+        .collect();
+
+    let mut samples = Vec::with_capacity(handles.len());
+    for handle in handles {
+        if let Ok(Ok(sample)) = handle.await {
+            samples.push(sample);
+        }
+    }
+    samples
+}
+
+pub async fn collect_metrics() -> SystemMetrics {
+    let mut merged = MetricSample::default();
+    for sample in collect_all().await {
+        merged.merge_missing(sample);
+    }
+
+    merged.apply_sensor_mapping(&app_settings::sensor_mapping());
+    merged.into_metrics()
+}
+
+/// Queries every provider, regardless of priority, and returns all sensors they report.
+pub async fn collect_sensors() -> Vec<SensorInfo> {
+    collect_all()
+        .await
+        .into_iter()
+        .flat_map(|sample| sample.sensors)
+        .collect()
+}
+
+/// Turns a free-form sensor label into an id segment, e.g. `"k10temp Tctl"` -> `"k10temp-tctl"`.
+pub fn slugify(label: &str) -> String {
+    let mut slug = String::new();
+    for c in label.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+fn default_priority() -> i32 {
+    1000
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_timeout_ms() -> u64 {
+    2000
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ProviderSource {
+    /// Runs a shell command and parses a number from its stdout.
+    Command { command: String },
+    /// Reads a number from a text file.
+    File { path: String },
+    /// Fetches JSON from a local HTTP endpoint and takes the value at a JSON pointer (e.g. `/data/temp`).
+    HttpJson { url: String, pointer: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderConfig {
+    pub id: String,
+    /// Built-in field name (e.g. `cpu_temperature`) or a custom slot key.
+    pub metric: String,
+    pub source: ProviderSource,
+    #[serde(default = "default_priority")]
+    pub priority: i32,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+/// Checks ids, metric names and sources of user-configured providers, so broken
+/// entries are rejected when saving.
+pub fn validate(providers: &[ProviderConfig]) -> Result<(), String> {
+    let built_in: Vec<String> = PROVIDERS
+        .read()
+        .map(|providers| providers.iter().map(|p| p.id()).collect())
+        .unwrap_or_default();
+    let mut ids = BTreeSet::new();
+
+    for config in providers {
+        let id = &config.id;
+        // Sensor ids are `<provider id>/value`, so the id must not contain a `/`
+        if id.is_empty()
+            || !id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(format!(
+                "Provider id '{}' may only contain letters, digits, '-' and '_'",
+                id
+            ));
+        }
+        if built_in.contains(id) || !ids.insert(id.as_str()) {
+            return Err(format!("Provider id '{}' is already in use", id));
+        }
+
+        let problem = if config.metric.trim().is_empty() {
+            Some("metric name is empty".to_string())
+        } else if config.metric == DISKS {
+            Some("disks cannot be set from a single value".to_string())
+        } else if config.timeout_ms == 0 {
+            Some("timeout must be above 0 ms".to_string())
+        } else {
+            match &config.source {
+                ProviderSource::Command { command } if command.trim().is_empty() => {
+                    Some("command is empty".to_string())
+                }
+                ProviderSource::File { path } if path.trim().is_empty() => {
+                    Some("file path is empty".to_string())
+                }
+                ProviderSource::HttpJson { url, pointer } => match reqwest::Url::parse(url) {
+                    Ok(url) if matches!(url.scheme(), "http" | "https") => (!pointer.is_empty()
+                        && !pointer.starts_with('/'))
+                    .then(|| format!("JSON pointer '{}' must start with '/'", pointer)),
+                    _ => Some(format!("'{}' is not an http(s) URL", url)),
+                },
+                _ => None,
+            }
+        };
+
+        if let Some(problem) = problem {
+            return Err(format!("Provider '{}': {}", id, problem));
+        }
+    }
+    Ok(())
+}
+
+struct ConfiguredProvider {
+    config: ProviderConfig,
+}
+
+#[async_trait]
+impl MetricProvider for ConfiguredProvider {
+    fn id(&self) -> String {
+        self.config.id.clone()
+    }
+
+    fn priority(&self) -> i32 {
+        self.config.priority
+    }
+
+    async fn collect(&self) -> Result<MetricSample, String> {
+        let value = read_source(&self.config).await?;
+        let mut sample = MetricSample::default();
+        sample.set_value(&self.config.metric, value);
+        sample.sensors.push(SensorInfo {
+            id: format!("{}/value", self.config.id),
+            label: self.config.metric.clone(),
+            kind: "custom".to_string(),
+            unit: String::new(),
+            value: Some(value),
+            provider: self.config.id.clone(),
+        });
+        Ok(sample)
+    }
+}
+
+async fn read_source(config: &ProviderConfig) -> Result<f64, String> {
+    let timeout = Duration::from_millis(config.timeout_ms);
+
+    match &config.source {
+        ProviderSource::Command { command } => {
+            let output = tokio::time::timeout(timeout, shell_command(command).output())
+                .await
+                .map_err(|_| format!("Command timed out after {}ms", config.timeout_ms))?
+                .map_err(|e| format!("Failed to run command: {}", e))?;
+
+            if !output.status.success() {
+                return Err(format!("Command exited with status: {}", output.status));
+            }
+
+            parse_number(&String::from_utf8_lossy(&output.stdout))
+        }
+        ProviderSource::File { path } => {
+            let text = tokio::fs::read_to_string(path)
+                .await
+                .map_err(|e| format!("Failed to read file: {}", e))?;
+
+            parse_number(&text)
+        }
+        ProviderSource::HttpJson { url, pointer } => {
+            let client = reqwest::Client::builder()
+                .timeout(timeout)
+                .build()
+                .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+            let response = client
+                .get(url)
+                .send()
+                .await
+                .map_err(|e| format!("Failed to request endpoint: {}", e))?;
+
+            if !response.status().is_success() {
+                return Err(format!("Endpoint returned status: {}", response.status()));
+            }
+
+            let json: serde_json::Value = response
+                .json()
+                .await
+                .map_err(|e| format!("Failed to parse response: {}", e))?;
+
+            let value = json
+                .pointer(pointer)
+                .ok_or_else(|| format!("No value at '{}'", pointer))?;
+
+            match value {
+                serde_json::Value::Number(n) => n
+                    .as_f64()
+                    .ok_or_else(|| format!("Value at '{}' is not a number", pointer)),
+                serde_json::Value::String(s) => parse_number(s),
+                _ => Err(format!("Value at '{}' is not a number", pointer)),
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn shell_command(command: &str) -> tokio::process::Command {
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+    let mut cmd = tokio::process::Command::new("cmd");
+    cmd.args(["/C", command])
+        .creation_flags(CREATE_NO_WINDOW)
+        .kill_on_drop(true);
+    cmd
+}
+
+#[cfg(not(target_os = "windows"))]
+fn shell_command(command: &str) -> tokio::process::Command {
+    let mut cmd = tokio::process::Command::new("sh");
+    cmd.args(["-c", command]).kill_on_drop(true);
+    cmd
+}
+
+/// Accepts either a bare number or text where the first numeric token is the value
+/// (e.g. `"42.5 C"` or `"queue: 7"`).
+fn parse_number(text: &str) -> Result<f64, String> {
+    let trimmed = text.trim();
+    if let Ok(value) = trimmed.parse::<f64>() {
+        return Ok(value);
+    }
+
+    trimmed
+        .split(|c: char| c.is_whitespace() || c == ':' || c == '=' || c == ',')
+        .find_map(|token| token.parse::<f64>().ok())
+        .ok_or_else(|| format!("No number found in '{}'", trimmed))
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn get_sensor_catalog() -> Result<Vec<SensorInfo>, String> {
+    Ok(collect_sensors().await)
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn test_metric_provider(config: ProviderConfig) -> Result<f64, String> {
+    read_source(&config).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provider(id: &str, metric: &str, source: ProviderSource) -> ProviderConfig {
+        ProviderConfig {
+            id: id.to_string(),
+            metric: metric.to_string(),
+            source,
+            priority: default_priority(),
+            enabled: true,
+            timeout_ms: default_timeout_ms(),
+        }
+    }
+
+    fn command(command: &str) -> ProviderSource {
+        ProviderSource::Command {
+            command: command.to_string(),
+        }
+    }
+
+    fn http(url: &str, pointer: &str) -> ProviderSource {
+        ProviderSource::HttpJson {
+            url: url.to_string(),
+            pointer: pointer.to_string(),
+        }
+    }
+
+    #[test]
+    fn valid_providers_are_accepted() {
+        let providers = [
+            provider("queue", "build_queue", command("echo 7")),
+            provider(
+                "cpu-temp",
+                CPU_TEMPERATURE,
+                http("http://localhost:9000/t", "/data/temp"),
+            ),
+            provider("room", "room_temp", http("https://sensor.local", "")),
+        ];
+
+        assert_eq!(validate(&providers), Ok(()));
+    }
+
+    #[test]
+    fn broken_providers_are_rejected() {
+        let cases = [
+            vec![provider("", "queue", command("echo 7"))],
+            vec![provider("a/b", "queue", command("echo 7"))],
+            vec![
+                provider("same", "one", command("echo 1")),
+                provider("same", "two", command("echo 2")),
+            ],
+            vec![provider("queue", " ", command("echo 7"))],
+            vec![provider("queue", DISKS, command("echo 7"))],
+            vec![provider("queue", "queue", command(""))],
+            vec![provider("queue", "queue", http("ftp://host/x", "/a"))],
+            vec![provider("queue", "queue", http("http://host/x", "a"))],
+        ];
+
+        for providers in cases {
+            assert!(validate(&providers).is_err(), "{:?}", providers);
+        }
+    }
+
+    #[test]
+    fn disks_are_not_touched_by_single_values() {
+        let mut sample = MetricSample::default();
+        sample.set_value(DISKS, 5.0);
+        sample.set_value("queue", 7.0);
+
+        assert!(sample.disks.is_none());
+        assert!(!sample.custom.contains_key(DISKS));
+        assert_eq!(sample.custom.get("queue"), Some(&7.0));
+    }
+}
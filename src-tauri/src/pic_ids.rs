@@ -0,0 +1,253 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use divoom::MAX_PIC_ID;
+
+use crate::app_settings;
+use crate::divoom_api;
+
+const LOCK_TIMEOUT: Duration = Duration::from_secs(15);
+const LOCK_RETRY: Duration = Duration::from_millis(50);
+// Left behind by a process that died while holding it
+const STALE_LOCK: Duration = Duration::from_secs(60);
+
+// One lock per device IP, so a device that is slow to answer only holds up its own
+// uploads; `pic_ids.json.lock` guards the file shared by the app and the CLI
+static DEVICE_LOCKS: Mutex<BTreeMap<String, Arc<tokio::sync::Mutex<()>>>> =
+    Mutex::new(BTreeMap::new());
+// Last PicID used per device IP when there is no data directory to keep it in
+static IN_MEMORY: Mutex<BTreeMap<String, u32>> = Mutex::new(BTreeMap::new());
+
+fn file_path() -> Option<PathBuf> {
+    app_settings::data_dir().map(|dir| dir.join("pic_ids.json"))
+}
+
+fn device_lock(ip_address: &str) -> Arc<tokio::sync::Mutex<()>> {
+    let mut locks = DEVICE_LOCKS.lock().unwrap_or_else(|e| e.into_inner());
+    locks.entry(ip_address.to_string()).or_default().clone()
+}
+
+/// Lock file, removed again when dropped.
+struct FileLock(PathBuf);
+
+impl FileLock {
+    async fn acquire(path: &Path) -> Result<Self, String> {
+        let lock_path = path.with_extension("json.lock");
+        let started = Instant::now();
+
+        loop {
+            match tokio::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&lock_path)
+                .await
+            {
+                Ok(_) => return Ok(Self(lock_path)),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                    let stale = tokio::fs::metadata(&lock_path)
+                        .await
+                        .and_then(|meta| meta.modified())
+                        .ok()
+                        .and_then(|modified| modified.elapsed().ok())
+                        .is_some_and(|age| age > STALE_LOCK);
+                    if stale {
+                        let _ = tokio::fs::remove_file(&lock_path).await;
+                        continue;
+                    }
+                    if started.elapsed() > LOCK_TIMEOUT {
+                        return Err(format!(
+                            "PicIDs are locked by another process, remove {} if none is running",
+                            lock_path.display()
+                        ));
+                    }
+                    tokio::time::sleep(LOCK_RETRY).await;
+                }
+                Err(e) => return Err(format!("Failed to lock PicIDs: {}", e)),
+            }
+        }
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// Applies `change` to the last PicID per device IP stored at `path`, re-read under
+/// the lock file so changes made by another process in the meantime are kept.
+async fn update<R>(
+    path: Option<&Path>,
+    change: impl FnOnce(&mut BTreeMap<String, u32>) -> R,
+) -> Result<R, String> {
+    let Some(path) = path else {
+        let mut ids = IN_MEMORY.lock().unwrap_or_else(|e| e.into_inner());
+        return Ok(change(&mut ids));
+    };
+
+    let _lock = FileLock::acquire(path).await?;
+    // A missing or unreadable file only costs a query or reset of the devices
+    let mut ids: BTreeMap<String, u32> = tokio::fs::read_to_string(path)
+        .await
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default();
+
+    let result = change(&mut ids);
+
+    let data = serde_json::to_string_pretty(&ids)
+        .map_err(|e| format!("Failed to serialize PicIDs: {}", e))?;
+    tokio::fs::write(path, data)
+        .await
+        .map_err(|e| format!("Failed to save PicIDs: {}", e))?;
+    Ok(result)
+}
+
+/// Takes the next `count` ids of a known device, unless that would pass the limit.
+fn reserve(ids: &mut BTreeMap<String, u32>, ip_address: &str, count: u32) -> Option<u32> {
+    let last = *ids.get(ip_address)?;
+    let next = last.checked_add(count).filter(|next| *next <= MAX_PIC_ID)?;
+    ids.insert(ip_address.to_string(), next);
+    Some(last + 1)
+}
+
+/// Where a device's sequence continues: after the last PicID it reported, or
+/// `None` when the device has to be reset first.
+fn continue_after(device_last: Option<u32>, count: u32) -> Option<u32> {
+    device_last.filter(|last| last.saturating_add(count) <= MAX_PIC_ID)
+}
+
+/// Reserves `count` consecutive PicIDs for an upload to `ip_address` and returns the first.
+///
+/// Devices seen for the first time continue after the last PicID they report, and
+/// the device's sequence is reset before it would pass `divoom::MAX_PIC_ID`.
+pub async fn allocate(ip_address: &str, count: u32) -> Result<u32, String> {
+    let lock = device_lock(ip_address);
+    let _device = lock.lock().await;
+    let path = file_path();
+
+    let (first, known) = update(path.as_deref(), |ids| {
+        (
+            reserve(ids, ip_address, count),
+            ids.contains_key(ip_address),
+        )
+    })
+    .await?;
+    if let Some(first) = first {
+        return Ok(first);
+    }
+
+    // The device is asked without holding the file lock, so other devices don't wait
+    let device_last = if known {
+        None
+    } else {
+        divoom_api::client(ip_address).get_pic_id().await.ok()
+    };
+    let last = match continue_after(device_last, count) {
+        Some(last) => last,
+        None => {
+            reset_device(ip_address).await?;
+            0
+        }
+    };
+
+    update(path.as_deref(), |ids| {
+        // Another process may have moved on meanwhile; higher ids are always safe
+        let stored = continue_after(ids.get(ip_address).copied(), count).unwrap_or(0);
+        ids.insert(ip_address.to_string(), stored.max(last));
+        reserve(ids, ip_address, count)
+    })
+    .await?
+    .ok_or_else(|| format!("Cannot reserve {} PicIDs", count))
+}
+
+async fn reset_device(ip_address: &str) -> Result<(), String> {
+    divoom_api::client(ip_address)
+        .reset_pic_id()
+        .await
+        .map_err(|e| format!("Failed to reset PicID: {}", e))
+}
+
+/// Starts the device's PicID sequence over, e.g. when uploads keep showing an old picture.
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn reset_pic_id(ip_address: String) -> Result<(), String> {
+    let lock = device_lock(&ip_address);
+    let _device = lock.lock().await;
+
+    reset_device(&ip_address).await?;
+    update(file_path().as_deref(), |ids| {
+        ids.insert(ip_address.clone(), 0);
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IP: &str = "192.168.1.50";
+
+    #[test]
+    fn reserve_hands_out_consecutive_ids() {
+        let mut ids = BTreeMap::from([(IP.to_string(), 10)]);
+
+        assert_eq!(reserve(&mut ids, IP, 1), Some(11));
+        assert_eq!(reserve(&mut ids, IP, 5), Some(12));
+        assert_eq!(ids[IP], 16);
+        assert_eq!(reserve(&mut ids, "10.0.0.1", 1), None);
+    }
+
+    #[test]
+    fn reserve_stops_at_the_limit() {
+        let mut ids = BTreeMap::from([(IP.to_string(), MAX_PIC_ID - 5)]);
+
+        assert_eq!(reserve(&mut ids, IP, 5), Some(MAX_PIC_ID - 4));
+        assert_eq!(reserve(&mut ids, IP, 1), None);
+        // A failed reservation leaves the sequence alone
+        assert_eq!(ids[IP], MAX_PIC_ID);
+
+        ids.insert(IP.to_string(), u32::MAX);
+        assert_eq!(reserve(&mut ids, IP, 1), None);
+    }
+
+    #[test]
+    fn devices_near_the_limit_are_reset() {
+        assert_eq!(continue_after(Some(40), 5), Some(40));
+        assert_eq!(
+            continue_after(Some(MAX_PIC_ID - 5), 5),
+            Some(MAX_PIC_ID - 5)
+        );
+        assert_eq!(continue_after(Some(MAX_PIC_ID - 4), 5), None);
+        assert_eq!(continue_after(None, 1), None);
+    }
+
+    #[tokio::test]
+    async fn updates_are_saved_and_keep_other_devices() {
+        let dir = std::env::temp_dir().join(format!("pic-ids-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("pic_ids.json");
+        std::fs::write(&path, r#"{"10.0.0.1": 7}"#).unwrap();
+
+        let first = update(Some(&path), |ids| {
+            ids.insert(IP.to_string(), 0);
+            reserve(ids, IP, 5)
+        })
+        .await
+        .unwrap();
+        let saved: BTreeMap<String, u32> =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+
+        assert_eq!(first, Some(1));
+        assert_eq!(
+            saved,
+            BTreeMap::from([("10.0.0.1".to_string(), 7), (IP.to_string(), 5)])
+        );
+        // The lock file is gone once the update is done
+        assert!(!path.with_extension("json.lock").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,301 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+
+use crate::app_settings;
+use crate::draw_commands::{self, Animation};
+use crate::models::{DisplayItem, DisplayItemKind, TextConfig};
+use crate::runtime;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+const TRANSITION_FRAMES: u32 = 6;
+const TRANSITION_FRAME_MS: u32 = 80;
+
+static PLAYLIST_STATES: Mutex<BTreeMap<String, PlaylistState>> = Mutex::new(BTreeMap::new());
+
+fn default_dwell_secs() -> u64 {
+    10
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Played from the previous picture to the next one before the next is shown.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Transition {
+    #[default]
+    None,
+    Fade,
+    /// The next picture pushes the previous one out to the left.
+    Slide,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PlaylistItem {
+    /// Still image or animated GIF, from an http(s) URL or a local file.
+    Image { source: String },
+    /// Text elements over a background image, black when omitted.
+    Text {
+        #[serde(default)]
+        background: Option<String>,
+        texts: Vec<TextConfig>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlaylistEntry {
+    #[serde(flatten)]
+    pub item: PlaylistItem,
+    #[serde(default = "default_dwell_secs")]
+    pub dwell_secs: u64,
+    #[serde(default)]
+    pub transition: Transition,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Playlist {
+    pub id: String,
+    pub name: String,
+    pub ip_address: String,
+    pub lcd_index: u32,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub paused: bool,
+    #[serde(default)]
+    pub entries: Vec<PlaylistEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlaylistStatus {
+    pub id: String,
+    /// Entry on screen; `None` before the first one was shown.
+    pub position: Option<usize>,
+    pub paused: bool,
+    /// Seconds until the next entry, while playing.
+    pub next_in_secs: Option<u64>,
+    pub last_error: Option<String>,
+}
+
+#[derive(Default)]
+struct PlaylistState {
+    position: Option<usize>,
+    next_at: Option<Instant>,
+    busy: bool,
+    /// Last picture shown, 128x128, to start transitions from.
+    last_frame: Option<DynamicImage>,
+    /// Whether texts need clearing before the next entry.
+    has_texts: bool,
+    last_error: Option<String>,
+}
+
+fn blank_frame() -> DynamicImage {
+    DynamicImage::ImageRgba8(RgbaImage::from_pixel(
+        divoom::LCD_SIZE,
+        divoom::LCD_SIZE,
+        Rgba([0, 0, 0, 255]),
+    ))
+}
+
+fn fit(img: &DynamicImage) -> DynamicImage {
+    img.resize_exact(divoom::LCD_SIZE, divoom::LCD_SIZE, FilterType::Triangle)
+}
+
+fn transition_frames(
+    from: &DynamicImage,
+    to: &DynamicImage,
+    transition: Transition,
+) -> Vec<DynamicImage> {
+    let from = from.to_rgba8();
+    let to = to.to_rgba8();
+    let size = i64::from(divoom::LCD_SIZE);
+
+    (1..=TRANSITION_FRAMES)
+        .map(|step| {
+            let t = step as f32 / (TRANSITION_FRAMES + 1) as f32;
+            let frame = match transition {
+                Transition::Fade => RgbaImage::from_fn(to.width(), to.height(), |x, y| {
+                    let (a, b) = (from.get_pixel(x, y), to.get_pixel(x, y));
+                    Rgba(std::array::from_fn(|i| {
+                        (f32::from(a[i]) * (1.0 - t) + f32::from(b[i]) * t) as u8
+                    }))
+                }),
+                _ => {
+                    let offset = (size as f32 * t) as i64;
+                    let mut canvas = RgbaImage::new(to.width(), to.height());
+                    imageops::overlay(&mut canvas, &from, -offset, 0);
+                    imageops::overlay(&mut canvas, &to, size - offset, 0);
+                    canvas
+                }
+            };
+            DynamicImage::ImageRgba8(frame)
+        })
+        .collect()
+}
+
+/// Shows one entry and returns the new last frame and whether texts are now on screen.
+async fn show_entry(
+    playlist: &Playlist,
+    entry: &PlaylistEntry,
+    previous: Option<DynamicImage>,
+    has_texts: bool,
+) -> Result<(DynamicImage, bool), String> {
+    let ip = &playlist.ip_address;
+    let lcd = playlist.lcd_index;
+
+    if has_texts {
+        draw_commands::clear_screen_text(ip.clone(), lcd).await?;
+    }
+
+    let (animation, texts) = match &entry.item {
+        PlaylistItem::Image { source } => (draw_commands::load_animation(source).await?, &[][..]),
+        PlaylistItem::Text { background, texts } => {
+            let animation = match background {
+                Some(source) => draw_commands::load_animation(source).await?,
+                None => Animation {
+                    frames: vec![blank_frame()],
+                    speed_ms: 1000,
+                },
+            };
+            (animation, texts.as_slice())
+        }
+    };
+    let first = fit(&animation.frames[0]);
+
+    if let (Some(previous), Transition::Fade | Transition::Slide) = (previous, entry.transition) {
+        let transition = Animation {
+            frames: transition_frames(&previous, &first, entry.transition),
+            speed_ms: TRANSITION_FRAME_MS,
+        };
+        draw_commands::send_animation(ip, &[lcd], &transition).await?;
+        tokio::time::sleep(Duration::from_millis(u64::from(
+            TRANSITION_FRAMES * TRANSITION_FRAME_MS,
+        )))
+        .await;
+    }
+
+    draw_commands::send_animation(ip, &[lcd], &animation).await?;
+    if !texts.is_empty() {
+        let items = texts
+            .iter()
+            .map(|text| DisplayItem {
+                text: text.clone(),
+                kind: DisplayItemKind::Text,
+            })
+            .collect();
+        draw_commands::set_screen_items(ip.clone(), lcd, items).await?;
+    }
+
+    Ok((first, !texts.is_empty()))
+}
+
+/// Starts the background job that advances all enabled, unpaused playlists.
+pub fn start() {
+    runtime::spawn(async {
+        loop {
+            let playlists = app_settings::playlists();
+            let now = Instant::now();
+
+            if let Ok(mut states) = PLAYLIST_STATES.lock() {
+                states.retain(|id, _| playlists.iter().any(|p| &p.id == id));
+
+                for playlist in playlists {
+                    if !playlist.enabled || playlist.paused || playlist.entries.is_empty() {
+                        continue;
+                    }
+
+                    let state = states.entry(playlist.id.clone()).or_default();
+                    if state.busy || state.next_at.is_some_and(|at| at > now) {
+                        continue;
+                    }
+
+                    let position = state
+                        .position
+                        .map_or(0, |p| (p + 1) % playlist.entries.len());
+                    let previous = state.last_frame.take();
+                    let has_texts = std::mem::take(&mut state.has_texts);
+                    state.busy = true;
+
+                    runtime::spawn(async move {
+                        let entry = &playlist.entries[position];
+                        let result = show_entry(&playlist, entry, previous, has_texts).await;
+
+                        if let Ok(mut states) = PLAYLIST_STATES.lock() {
+                            let state = states.entry(playlist.id.clone()).or_default();
+                            state.busy = false;
+                            state.position = Some(position);
+                            state.next_at =
+                                Some(Instant::now() + Duration::from_secs(entry.dwell_secs.max(1)));
+                            match result {
+                                Ok((frame, has_texts)) => {
+                                    state.last_frame = Some(frame);
+                                    state.has_texts = has_texts;
+                                    state.last_error = None;
+                                }
+                                Err(e) => {
+                                    eprintln!("[Playlists] '{}': {}", playlist.id, e);
+                                    state.last_error = Some(e);
+                                }
+                            }
+                        }
+                    });
+                }
+            }
+
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn set_playlist_paused(playlist_id: String, paused: bool) -> Result<(), String> {
+    let mut playlists = app_settings::playlists();
+    let playlist = playlists
+        .iter_mut()
+        .find(|p| p.id == playlist_id)
+        .ok_or_else(|| format!("Playlist '{}' not found", playlist_id))?;
+    playlist.paused = paused;
+
+    app_settings::set_playlists(playlists);
+    Ok(())
+}
+
+/// Shows the next entry on the next check instead of waiting out the dwell time.
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn skip_playlist_entry(playlist_id: String) {
+    if let Ok(mut states) = PLAYLIST_STATES.lock() {
+        if let Some(state) = states.get_mut(&playlist_id) {
+            state.next_at = None;
+        }
+    }
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_playlist_status() -> Vec<PlaylistStatus> {
+    let states = PLAYLIST_STATES.lock();
+    let now = Instant::now();
+
+    app_settings::playlists()
+        .into_iter()
+        .map(|playlist| {
+            let state = states.as_ref().ok().and_then(|s| s.get(&playlist.id));
+            PlaylistStatus {
+                position: state.and_then(|s| s.position),
+                next_in_secs: state
+                    .filter(|_| playlist.enabled && !playlist.paused)
+                    .and_then(|s| s.next_at)
+                    .map(|at| at.saturating_duration_since(now).as_secs()),
+                last_error: state.and_then(|s| s.last_error.clone()),
+                paused: playlist.paused,
+                id: playlist.id,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,340 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use axum::http::header;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::Router;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use crate::divoom_api::{self, DeviceHealth};
+use crate::metric_providers;
+use crate::runtime;
+use crate::system_metrics::{DiskUsage, SystemMetrics};
+
+// Held while the listener is replaced, so two setting changes cannot race for the port
+static SERVER: Mutex<Option<runtime::JoinHandle<()>>> = Mutex::const_new(None);
+
+fn default_bind_address() -> String {
+    "127.0.0.1".to_string()
+}
+
+fn default_port() -> u16 {
+    9101
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PrometheusSettings {
+    pub enabled: bool,
+    #[serde(default = "default_bind_address")]
+    pub bind_address: String,
+    #[serde(default = "default_port")]
+    pub port: u16,
+}
+
+impl Default for PrometheusSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: default_bind_address(),
+            port: default_port(),
+        }
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+struct Exposition {
+    out: String,
+}
+
+impl Exposition {
+    fn metric(&mut self, name: &str, kind: &str, help: &str, samples: &[(String, f64)]) {
+        if samples.is_empty() {
+            return;
+        }
+
+        let _ = writeln!(self.out, "# HELP {} {}", name, help);
+        let _ = writeln!(self.out, "# TYPE {} {}", name, kind);
+        for (labels, value) in samples {
+            if labels.is_empty() {
+                let _ = writeln!(self.out, "{} {}", name, value);
+            } else {
+                let _ = writeln!(self.out, "{}{{{}}} {}", name, labels, value);
+            }
+        }
+    }
+
+    fn gauge(&mut self, name: &str, help: &str, value: Option<f64>) {
+        if let Some(value) = value {
+            self.metric(name, "gauge", help, &[(String::new(), value)]);
+        }
+    }
+}
+
+/// Renders system metrics and device health in the Prometheus text format.
+pub fn render(metrics: &SystemMetrics, health: &BTreeMap<String, DeviceHealth>) -> String {
+    let mut e = Exposition { out: String::new() };
+
+    e.gauge(
+        "divoom_monitor_cpu_usage_percent",
+        "CPU usage in percent.",
+        Some(metrics.cpu_usage as f64),
+    );
+    e.gauge(
+        "divoom_monitor_cpu_temperature_celsius",
+        "CPU temperature in degrees Celsius.",
+        metrics.cpu_temperature.map(|v| v as f64),
+    );
+    e.gauge(
+        "divoom_monitor_gpu_usage_percent",
+        "GPU usage in percent.",
+        metrics.gpu_usage.map(|v| v as f64),
+    );
+    e.gauge(
+        "divoom_monitor_gpu_temperature_celsius",
+        "GPU temperature in degrees Celsius.",
+        metrics.gpu_temperature.map(|v| v as f64),
+    );
+    e.gauge(
+        "divoom_monitor_memory_total_bytes",
+        "Total physical memory in bytes.",
+        Some(metrics.memory_total as f64),
+    );
+    e.gauge(
+        "divoom_monitor_memory_used_bytes",
+        "Used physical memory in bytes.",
+        Some(metrics.memory_used as f64),
+    );
+
+    let disk_labels = |d: &DiskUsage| {
+        format!(
+            "name=\"{}\",mount_point=\"{}\"",
+            escape_label(&d.name),
+            escape_label(&d.mount_point)
+        )
+    };
+    e.metric(
+        "divoom_monitor_disk_total_bytes",
+        "gauge",
+        "Total disk space in bytes.",
+        &metrics
+            .disks
+            .iter()
+            .map(|d| (disk_labels(d), d.total_space as f64))
+            .collect::<Vec<_>>(),
+    );
+    e.metric(
+        "divoom_monitor_disk_used_bytes",
+        "gauge",
+        "Used disk space in bytes.",
+        &metrics
+            .disks
+            .iter()
+            .map(|d| (disk_labels(d), d.used_space as f64))
+            .collect::<Vec<_>>(),
+    );
+    e.metric(
+        "divoom_monitor_disk_usage_percent",
+        "gauge",
+        "Used disk space in percent.",
+        &metrics
+            .disks
+            .iter()
+            .map(|d| (disk_labels(d), d.usage_percent as f64))
+            .collect::<Vec<_>>(),
+    );
+    e.metric(
+        "divoom_monitor_custom_metric",
+        "gauge",
+        "Values reported by user-configured metric providers.",
+        &metrics
+            .custom
+            .iter()
+            .map(|(name, value)| (format!("name=\"{}\"", escape_label(name)), *value))
+            .collect::<Vec<_>>(),
+    );
+
+    let device_samples = |f: &dyn Fn(&DeviceHealth) -> Option<f64>| {
+        health
+            .iter()
+            .filter_map(|(ip, h)| f(h).map(|v| (format!("ip=\"{}\"", escape_label(ip)), v)))
+            .collect::<Vec<_>>()
+    };
+    e.metric(
+        "divoom_device_command_latency_seconds",
+        "gauge",
+        "Round-trip time of the last command sent to the device.",
+        &device_samples(&|h| h.last_latency_ms.map(|ms| ms as f64 / 1000.0)),
+    );
+    e.metric(
+        "divoom_device_last_command_success",
+        "gauge",
+        "1 if the last command sent to the device succeeded, 0 otherwise.",
+        &device_samples(&|h| h.last_success.map(|ok| if ok { 1.0 } else { 0.0 })),
+    );
+    e.metric(
+        "divoom_device_last_command_timestamp_seconds",
+        "gauge",
+        "Unix time of the last finished command.",
+        &device_samples(&|h| h.last_command_at.map(|t| t as f64)),
+    );
+    e.metric(
+        "divoom_device_commands_in_flight",
+        "gauge",
+        "Commands currently waiting for a device response.",
+        &device_samples(&|h| Some(h.in_flight as f64)),
+    );
+
+    let totals: Vec<(String, f64)> = health
+        .iter()
+        .flat_map(|(ip, h)| {
+            let ip = escape_label(ip);
+            [
+                (
+                    format!("ip=\"{}\",result=\"ok\"", ip),
+                    h.success_total as f64,
+                ),
+                (
+                    format!("ip=\"{}\",result=\"error\"", ip),
+                    h.error_total as f64,
+                ),
+            ]
+        })
+        .collect();
+    e.metric(
+        "divoom_device_commands_total",
+        "counter",
+        "Commands sent to the device by result.",
+        &totals,
+    );
+
+    e.out
+}
+
+async fn metrics_handler() -> impl IntoResponse {
+    let metrics = metric_providers::collect_metrics().await;
+    let body = render(&metrics, &divoom_api::device_health());
+
+    (
+        [(
+            header::CONTENT_TYPE,
+            "text/plain; version=0.0.4; charset=utf-8",
+        )],
+        body,
+    )
+}
+
+/// Stops the running listener, if any, and starts a new one when enabled; fails when
+/// the address cannot be bound.
+pub async fn apply(settings: PrometheusSettings) -> Result<(), String> {
+    let mut server = SERVER.lock().await;
+    if let Some(handle) = server.take() {
+        handle.abort();
+        // The listener is only released once the aborted task has finished
+        let _ = handle.await;
+    }
+
+    if !settings.enabled {
+        return Ok(());
+    }
+
+    let addr = format!("{}:{}", settings.bind_address, settings.port);
+    let listener = tokio::net::TcpListener::bind(&addr)
+        .await
+        .map_err(|e| format!("Failed to bind {}: {}", addr, e))?;
+
+    eprintln!("[Prometheus] Serving metrics on http://{}/metrics", addr);
+    *server = Some(runtime::spawn(async move {
+        let app = Router::new().route("/metrics", get(metrics_handler));
+        if let Err(e) = axum::serve(listener, app).await {
+            eprintln!("[Prometheus] Server stopped: {}", e);
+        }
+    }));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics() -> SystemMetrics {
+        SystemMetrics {
+            cpu_usage: 12.5,
+            cpu_temperature: None,
+            gpu_usage: None,
+            gpu_temperature: None,
+            memory_total: 1024,
+            memory_used: 512,
+            disks: vec![DiskUsage {
+                name: "Data \"D\"".to_string(),
+                mount_point: "D:\\".to_string(),
+                total_space: 100,
+                available_space: 25,
+                used_space: 75,
+                usage_percent: 75.0,
+            }],
+            custom: BTreeMap::from([("line\nbreak".to_string(), 1.5)]),
+        }
+    }
+
+    #[test]
+    fn label_values_are_escaped() {
+        assert_eq!(escape_label(r#"a\b"c"#), r#"a\\b\"c"#);
+        assert_eq!(escape_label("one\ntwo"), r"one\ntwo");
+
+        let out = render(&metrics(), &BTreeMap::new());
+        assert!(out.contains(
+            "divoom_monitor_disk_used_bytes{name=\"Data \\\"D\\\"\",mount_point=\"D:\\\\\"} 75\n"
+        ));
+        assert!(out.contains("divoom_monitor_custom_metric{name=\"line\\nbreak\"} 1.5\n"));
+    }
+
+    #[test]
+    fn missing_values_leave_out_the_whole_metric() {
+        let out = render(&metrics(), &BTreeMap::new());
+
+        assert!(out.contains(
+            "# HELP divoom_monitor_cpu_usage_percent CPU usage in percent.\n\
+             # TYPE divoom_monitor_cpu_usage_percent gauge\n\
+             divoom_monitor_cpu_usage_percent 12.5\n"
+        ));
+        assert!(!out.contains("cpu_temperature"));
+        assert!(!out.contains("divoom_device_"));
+    }
+
+    #[test]
+    fn device_health_is_labelled_by_ip() {
+        let health = BTreeMap::from([(
+            "192.168.1.50".to_string(),
+            DeviceHealth {
+                last_latency_ms: Some(250),
+                last_success: Some(false),
+                last_error: Some("timeout".to_string()),
+                last_command_at: Some(1_700_000_000),
+                in_flight: 0,
+                success_total: 3,
+                error_total: 1,
+            },
+        )]);
+        let out = render(&metrics(), &health);
+
+        for line in [
+            "divoom_device_command_latency_seconds{ip=\"192.168.1.50\"} 0.25",
+            "divoom_device_last_command_success{ip=\"192.168.1.50\"} 0",
+            "divoom_device_commands_in_flight{ip=\"192.168.1.50\"} 0",
+            "# TYPE divoom_device_commands_total counter",
+            "divoom_device_commands_total{ip=\"192.168.1.50\",result=\"ok\"} 3",
+            "divoom_device_commands_total{ip=\"192.168.1.50\",result=\"error\"} 1",
+        ] {
+            assert!(out.lines().any(|l| l == line), "missing {}", line);
+        }
+    }
+}
@@ -0,0 +1,461 @@
+use std::sync::Arc;
+
+use axum::body::Bytes;
+use axum::extract::{DefaultBodyLimit, Path, Query, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use schemars::{schema_for, JsonSchema};
+use serde::{Deserialize, Serialize};
+use subtle::ConstantTimeEq;
+use tokio::sync::Mutex;
+
+use crate::device_commands;
+use crate::divoom_api;
+use crate::draw_commands;
+use crate::models::{BuzzerPattern, BuzzerTiming, DeviceSettings, DivoomDevice, TextConfig};
+use crate::runtime;
+
+const MAX_IMAGE_BYTES: usize = 20 * 1024 * 1024;
+
+// Held while the server is replaced, so two setting changes cannot race for the port
+static SERVER: Mutex<Option<RunningServer>> = Mutex::const_new(None);
+
+struct RunningServer {
+    addr: String,
+    token: String,
+    handle: runtime::JoinHandle<()>,
+}
+
+impl RunningServer {
+    fn start(listener: tokio::net::TcpListener, addr: String, token: String) -> Self {
+        eprintln!("[REST API] Listening on http://{}/api", addr);
+        let app = router(token.clone());
+        let handle = runtime::spawn(async move {
+            if let Err(e) = axum::serve(listener, app).await {
+                eprintln!("[REST API] Server stopped: {}", e);
+            }
+        });
+        Self {
+            addr,
+            token,
+            handle,
+        }
+    }
+
+    async fn stop(self) {
+        self.handle.abort();
+        // The listener is only released once the aborted task has finished
+        let _ = self.handle.await;
+    }
+}
+
+fn default_bind_address() -> String {
+    "127.0.0.1".to_string()
+}
+
+fn default_port() -> u16 {
+    9102
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RestApiSettings {
+    pub enabled: bool,
+    #[serde(default = "default_bind_address")]
+    pub bind_address: String,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    /// Clients must send `Authorization: Bearer <token>`.
+    #[serde(default)]
+    pub token: String,
+}
+
+impl Default for RestApiSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: default_bind_address(),
+            port: default_port(),
+            token: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct BrightnessRequest {
+    /// 0-100
+    pub value: u8,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ScreenRequest {
+    pub on: bool,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct TextRequest {
+    pub screen_index: u32,
+    pub text_config: TextConfig,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ImageUrlRequest {
+    #[serde(default)]
+    pub screen_index: Option<u32>,
+    /// Several screens showing the same image, instead of `screen_index`.
+    #[serde(default)]
+    pub screen_indexes: Vec<u32>,
+    pub url: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ImageBytesQuery {
+    #[serde(default)]
+    pub screen_index: Option<u32>,
+    /// Comma separated, e.g. `1,3,4`.
+    #[serde(default)]
+    pub screen_indexes: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct PcMetricsRequest {
+    pub lcd_index: u32,
+    /// Six PC monitor values; when omitted the app's own system metrics are sent.
+    #[serde(default)]
+    pub disp_data: Option<Vec<String>>,
+}
+
+/// Either a named pattern or explicit timings.
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum BuzzerRequest {
+    Pattern { pattern: BuzzerPattern },
+    Timing(BuzzerTiming),
+}
+
+#[derive(Serialize)]
+struct ApiOk {
+    ok: bool,
+}
+
+struct ApiError(StatusCode, String);
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(serde_json::json!({ "error": self.1 }))).into_response()
+    }
+}
+
+/// Errors of the app's commands come from talking to the device or fetching a source;
+/// handlers check the request with `bad_request` errors before calling them.
+impl From<String> for ApiError {
+    fn from(e: String) -> Self {
+        ApiError(StatusCode::BAD_GATEWAY, e)
+    }
+}
+
+impl From<divoom::Error> for ApiError {
+    fn from(e: divoom::Error) -> Self {
+        let status = match e {
+            divoom::Error::InvalidArgument(_) => StatusCode::BAD_REQUEST,
+            _ => StatusCode::BAD_GATEWAY,
+        };
+        ApiError(status, e.to_string())
+    }
+}
+
+fn bad_request(e: String) -> ApiError {
+    ApiError(StatusCode::BAD_REQUEST, e)
+}
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+fn ok() -> ApiResult<ApiOk> {
+    Ok(Json(ApiOk { ok: true }))
+}
+
+async fn authorize(
+    State(token): State<Arc<String>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let provided = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+
+    let valid =
+        provided.is_some_and(|provided| bool::from(provided.as_bytes().ct_eq(token.as_bytes())));
+    if !valid {
+        return Err(ApiError(
+            StatusCode::UNAUTHORIZED,
+            "Missing or invalid bearer token".to_string(),
+        ));
+    }
+
+    Ok(next.run(request).await)
+}
+
+async fn list_devices() -> ApiResult<Vec<DivoomDevice>> {
+    Ok(Json(device_commands::scan_devices().await?))
+}
+
+async fn device_info(Path(ip): Path<String>) -> ApiResult<DeviceSettings> {
+    Ok(Json(device_commands::get_device_info(ip).await?))
+}
+
+async fn set_brightness(
+    Path(ip): Path<String>,
+    Json(body): Json<BrightnessRequest>,
+) -> ApiResult<ApiOk> {
+    if body.value > 100 {
+        return Err(ApiError(
+            StatusCode::BAD_REQUEST,
+            "Brightness must be between 0 and 100".to_string(),
+        ));
+    }
+    divoom_api::client(&ip).set_brightness(body.value).await?;
+    ok()
+}
+
+async fn set_screen(Path(ip): Path<String>, Json(body): Json<ScreenRequest>) -> ApiResult<ApiOk> {
+    divoom_api::client(&ip).set_screen_on(body.on).await?;
+    ok()
+}
+
+async fn push_text(Path(ip): Path<String>, Json(body): Json<TextRequest>) -> ApiResult<ApiOk> {
+    device_commands::check_lcds(&ip, &[body.screen_index])
+        .await
+        .map_err(bad_request)?;
+    device_commands::check_texts(&ip, [&body.text_config])
+        .await
+        .map_err(bad_request)?;
+
+    divoom_api::client(&ip)
+        .send_text(body.screen_index, &body.text_config)
+        .await?;
+    ok()
+}
+
+/// Joins both ways of naming target screens and checks the device has them and
+/// takes images.
+async fn screen_targets(
+    ip: &str,
+    screen_index: Option<u32>,
+    mut screen_indexes: Vec<u32>,
+) -> Result<Vec<u32>, ApiError> {
+    screen_indexes.extend(screen_index);
+    device_commands::check_lcds(ip, &screen_indexes)
+        .await
+        .map_err(bad_request)?;
+    device_commands::check_image_uploads(ip)
+        .await
+        .map_err(bad_request)?;
+    Ok(screen_indexes)
+}
+
+async fn upload_image_url(
+    Path(ip): Path<String>,
+    Json(body): Json<ImageUrlRequest>,
+) -> ApiResult<ApiOk> {
+    let targets = screen_targets(&ip, body.screen_index, body.screen_indexes).await?;
+    draw_commands::upload_image_from_url(ip, targets, body.url).await?;
+    ok()
+}
+
+async fn upload_image_bytes(
+    Path(ip): Path<String>,
+    Query(query): Query<ImageBytesQuery>,
+    body: Bytes,
+) -> ApiResult<ApiOk> {
+    let img = image::load_from_memory(&body).map_err(|e| {
+        ApiError(
+            StatusCode::BAD_REQUEST,
+            format!("Failed to decode image: {}", e),
+        )
+    })?;
+    let screen_indexes = query
+        .screen_indexes
+        .iter()
+        .flat_map(|list| list.split(','))
+        .map(|index| {
+            index.trim().parse::<u32>().map_err(|_| {
+                ApiError(
+                    StatusCode::BAD_REQUEST,
+                    format!("Invalid screen index '{}'", index),
+                )
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let targets = screen_targets(&ip, query.screen_index, screen_indexes).await?;
+    draw_commands::send_image(&ip, &targets, img).await?;
+    ok()
+}
+
+async fn push_pc_metrics(
+    Path(ip): Path<String>,
+    Json(body): Json<PcMetricsRequest>,
+) -> ApiResult<ApiOk> {
+    device_commands::check_lcds(&ip, &[body.lcd_index])
+        .await
+        .map_err(bad_request)?;
+
+    match body.disp_data {
+        Some(disp_data) => {
+            divoom_api::client(&ip)
+                .update_pc_monitor(body.lcd_index, &disp_data)
+                .await?
+        }
+        None => draw_commands::send_system_metrics(ip, body.lcd_index).await?,
+    }
+    ok()
+}
+
+async fn play_buzzer(Path(ip): Path<String>, Json(body): Json<BuzzerRequest>) -> ApiResult<ApiOk> {
+    let timing = match body {
+        BuzzerRequest::Pattern { pattern } => pattern.timing(),
+        BuzzerRequest::Timing(timing) => timing,
+    };
+    divoom_api::client(&ip).play_buzzer(timing).await?;
+    ok()
+}
+
+async fn reboot(Path(ip): Path<String>) -> ApiResult<ApiOk> {
+    divoom_api::client(&ip).reboot().await?;
+    ok()
+}
+
+async fn schemas() -> Json<serde_json::Value> {
+    Json(serde_json::json!({
+        "POST /api/devices/:ip/brightness": schema_for!(BrightnessRequest),
+        "POST /api/devices/:ip/screen": schema_for!(ScreenRequest),
+        "POST /api/devices/:ip/text": schema_for!(TextRequest),
+        "POST /api/devices/:ip/image?screen_index=N or ?screen_indexes=1,3,4 (raw image bytes)":
+            schema_for!(ImageBytesQuery),
+        "POST /api/devices/:ip/image-url": schema_for!(ImageUrlRequest),
+        "POST /api/devices/:ip/pc-metrics": schema_for!(PcMetricsRequest),
+        "POST /api/devices/:ip/buzzer": schema_for!(BuzzerRequest),
+    }))
+}
+
+fn router(token: String) -> Router {
+    let api = Router::new()
+        .route("/api/devices", get(list_devices))
+        .route("/api/devices/:ip", get(device_info))
+        .route("/api/devices/:ip/brightness", post(set_brightness))
+        .route("/api/devices/:ip/screen", post(set_screen))
+        .route("/api/devices/:ip/text", post(push_text))
+        .route(
+            "/api/devices/:ip/image",
+            post(upload_image_bytes).layer(DefaultBodyLimit::max(MAX_IMAGE_BYTES)),
+        )
+        .route("/api/devices/:ip/image-url", post(upload_image_url))
+        .route("/api/devices/:ip/pc-metrics", post(push_pc_metrics))
+        .route("/api/devices/:ip/buzzer", post(play_buzzer))
+        .route("/api/devices/:ip/reboot", post(reboot))
+        .route_layer(middleware::from_fn_with_state(Arc::new(token), authorize));
+
+    Router::new().route("/api/schema", get(schemas)).merge(api)
+}
+
+async fn bind(addr: &str) -> Result<tokio::net::TcpListener, String> {
+    tokio::net::TcpListener::bind(addr)
+        .await
+        .map_err(|e| format!("Failed to bind {}: {}", addr, e))
+}
+
+/// Replaces the running API server with one for `settings`, or stops it when disabled.
+/// The running server is kept when the new address cannot be bound.
+pub async fn apply(settings: RestApiSettings) -> Result<(), String> {
+    if settings.enabled && settings.token.trim().is_empty() {
+        return Err("A bearer token is required to enable the REST API".to_string());
+    }
+
+    let mut server = SERVER.lock().await;
+    if !settings.enabled {
+        if let Some(running) = server.take() {
+            running.stop().await;
+        }
+        return Ok(());
+    }
+
+    let addr = format!("{}:{}", settings.bind_address, settings.port);
+    let listener = match bind(&addr).await {
+        Ok(listener) => listener,
+        // The address may be held by the running server itself, which has to go first
+        Err(e) => {
+            let Some(running) = server.take() else {
+                return Err(e);
+            };
+            let (old_addr, old_token) = (running.addr.clone(), running.token.clone());
+            running.stop().await;
+            match bind(&addr).await {
+                Ok(listener) => listener,
+                Err(e) => {
+                    if let Ok(listener) = bind(&old_addr).await {
+                        *server = Some(RunningServer::start(listener, old_addr, old_token));
+                    }
+                    return Err(e);
+                }
+            }
+        }
+    };
+
+    if let Some(running) = server.take() {
+        running.stop().await;
+    }
+    *server = Some(RunningServer::start(listener, addr, settings.token));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn serve(token: &str) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = router(token.to_string());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn requests_need_the_bearer_token() {
+        let base = serve("secret").await;
+        let client = reqwest::Client::new();
+        // Rejected by validation once through, so no device is contacted
+        let brightness = |auth: Option<&str>| {
+            let request = client
+                .post(format!("{}/api/devices/192.0.2.1/brightness", base))
+                .json(&serde_json::json!({ "value": 200 }));
+            match auth {
+                Some(auth) => request.header(header::AUTHORIZATION, auth),
+                None => request,
+            }
+        };
+
+        for auth in [None, Some("Bearer wrong"), Some("secret"), Some("Bearer ")] {
+            let status = brightness(auth).send().await.unwrap().status();
+            assert_eq!(status, StatusCode::UNAUTHORIZED, "{:?}", auth);
+        }
+        let status = brightness(Some("Bearer secret"))
+            .send()
+            .await
+            .unwrap()
+            .status();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        // The schema stays readable without a token
+        let status = client
+            .get(format!("{}/api/schema", base))
+            .send()
+            .await
+            .unwrap()
+            .status();
+        assert_eq!(status, StatusCode::OK);
+    }
+}
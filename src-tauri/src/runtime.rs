@@ -0,0 +1,7 @@
+//! Background task helpers that work with and without the Tauri runtime.
+
+#[cfg(feature = "gui")]
+pub use tauri::async_runtime::{spawn, JoinHandle};
+
+#[cfg(not(feature = "gui"))]
+pub use tokio::task::{spawn, JoinHandle};
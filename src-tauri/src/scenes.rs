@@ -0,0 +1,370 @@
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use divoom::PC_MONITOR_CLOCK_ID;
+use serde::{Deserialize, Serialize};
+
+use crate::app_settings;
+use crate::channels;
+use crate::device_commands;
+use crate::divoom_api::{self, discover_via_divoom_api};
+use crate::draw_commands;
+use crate::models::{DisplayItem, DisplayItemKind, TextConfig};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SceneScreen {
+    pub lcd_index: u32,
+    /// Clock face selected before the image and texts are drawn.
+    #[serde(default)]
+    pub clock_id: Option<u64>,
+    /// http(s) URL or local file path; captured scenes point at a copy in the scenes
+    /// folder, so they keep working once the original is gone.
+    #[serde(default)]
+    pub image: Option<String>,
+    #[serde(default)]
+    pub texts: Vec<TextConfig>,
+}
+
+/// Everything needed to bring a device's screens back to one layout.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Scene {
+    pub name: String,
+    /// Device type the scene was captured on, e.g. `Times Gate`; other types are refused.
+    #[serde(default)]
+    pub device_type: Option<String>,
+    #[serde(default)]
+    pub brightness: Option<u8>,
+    #[serde(default)]
+    pub screens: Vec<SceneScreen>,
+    /// LCD switched to PC Monitor, if any; metrics are pushed by the app as usual.
+    #[serde(default)]
+    pub pc_monitor_lcd: Option<u32>,
+}
+
+fn scenes_dir() -> Result<PathBuf, String> {
+    app_settings::data_dir()
+        .map(|dir| dir.join("scenes"))
+        .ok_or_else(|| "Settings directory is not initialized".to_string())
+}
+
+/// File name, without extension, of the scene called `name`. Names that differ only
+/// in characters replaced by `_` share a file, so the stored name is checked before
+/// it is used.
+fn file_stem(name: &str) -> Result<String, String> {
+    if name.trim().is_empty() {
+        return Err("Scene name must not be empty".to_string());
+    }
+
+    Ok(name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect())
+}
+
+fn scene_path(dir: &Path, name: &str) -> Result<PathBuf, String> {
+    Ok(dir.join(format!("{}.json", file_stem(name)?)))
+}
+
+async fn device_type(ip_address: &str) -> Option<String> {
+    discover_via_divoom_api()
+        .await
+        .ok()?
+        .into_iter()
+        .find(|d| d.ip_address.as_deref() == Some(ip_address))
+        .map(|d| d.device_type)
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn list_scenes() -> Result<Vec<Scene>, String> {
+    let dir = scenes_dir()?;
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Ok(Vec::new());
+    };
+
+    let mut scenes: Vec<Scene> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .filter_map(|data| serde_json::from_str(&data).ok())
+        .collect();
+    scenes.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(scenes)
+}
+
+/// The scene stored in `name`'s file, whatever name it was saved under.
+fn read_scene_file(dir: &Path, name: &str) -> Result<Option<Scene>, String> {
+    let Ok(data) = fs::read_to_string(scene_path(dir, name)?) else {
+        return Ok(None);
+    };
+    serde_json::from_str(&data)
+        .map(Some)
+        .map_err(|e| format!("Scene '{}' is invalid: {}", name, e))
+}
+
+/// Refuses `name` when its file holds a scene saved under another name.
+fn check_name(dir: &Path, name: &str) -> Result<(), String> {
+    if let Ok(Some(existing)) = read_scene_file(dir, name) {
+        if existing.name != name {
+            return Err(format!(
+                "Scene name '{}' is too similar to the existing scene '{}'",
+                name, existing.name
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn read_scene(dir: &Path, name: &str) -> Result<Scene, String> {
+    read_scene_file(dir, name)?
+        .filter(|scene| scene.name == name)
+        .ok_or_else(|| format!("Scene '{}' not found", name))
+}
+
+fn write_scene(dir: &Path, scene: &Scene) -> Result<(), String> {
+    check_name(dir, &scene.name)?;
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create scenes folder: {}", e))?;
+
+    let data = serde_json::to_string_pretty(scene)
+        .map_err(|e| format!("Failed to serialize scene: {}", e))?;
+    // Written next to the scene and renamed over it, so a crash never leaves half a file
+    let path = scene_path(dir, &scene.name)?;
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, data).map_err(|e| format!("Failed to save scene: {}", e))?;
+    fs::rename(&temp_path, &path).map_err(|e| {
+        let _ = fs::remove_file(&temp_path);
+        format!("Failed to save scene: {}", e)
+    })
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_scene(name: String) -> Result<Scene, String> {
+    read_scene(&scenes_dir()?, &name)
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn save_scene(scene: Scene) -> Result<(), String> {
+    write_scene(&scenes_dir()?, &scene)
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn delete_scene(name: String) -> Result<(), String> {
+    let dir = scenes_dir()?;
+    let scene = read_scene(&dir, &name)?;
+    fs::remove_file(scene_path(&dir, &name)?)
+        .map_err(|e| format!("Failed to delete scene: {}", e))?;
+
+    // Copies made by `capture_scene`; images elsewhere belong to the user
+    for image in scene
+        .screens
+        .iter()
+        .filter_map(|screen| screen.image.as_ref())
+    {
+        if Path::new(image).parent() == Some(dir.as_path()) {
+            let _ = fs::remove_file(image);
+        }
+    }
+    Ok(())
+}
+
+/// Copies the screen's image into the scenes folder as `<scene>-lcd<N>.png` and
+/// points the screen at the copy.
+async fn keep_image(dir: &Path, name: &str, screen: &mut SceneScreen) -> Result<(), String> {
+    let Some(source) = &screen.image else {
+        return Ok(());
+    };
+    let img = draw_commands::load_image(source).await?;
+
+    let mut png = Vec::new();
+    img.write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode image: {}", e))?;
+
+    let path = dir.join(format!("{}-lcd{}.png", file_stem(name)?, screen.lcd_index));
+    tokio::fs::create_dir_all(dir)
+        .await
+        .map_err(|e| format!("Failed to create scenes folder: {}", e))?;
+    tokio::fs::write(&path, png)
+        .await
+        .map_err(|e| format!("Failed to save image: {}", e))?;
+
+    screen.image = Some(path.to_string_lossy().into_owned());
+    Ok(())
+}
+
+/// Saves a scene from the editor's `screens`, adding what the device itself reports:
+/// brightness, the clock face of every LCD and the device type. Screen images are
+/// copied into the scenes folder.
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn capture_scene(
+    name: String,
+    ip_address: String,
+    mut screens: Vec<SceneScreen>,
+    pc_monitor_lcd: Option<u32>,
+) -> Result<Scene, String> {
+    // Checked before the image copies could overwrite another scene's
+    let dir = scenes_dir()?;
+    check_name(&dir, &name)?;
+
+    let settings = divoom_api::client(&ip_address)
+        .get_all_conf()
+        .await
+        .map_err(|e| format!("Failed to read device state: {}", e))?;
+
+    // The LCD layout comes from the cloud; without it the clock faces are left out
+    if let Ok(info) = draw_commands::get_lcd_info(ip_address.clone()).await {
+        let clocks = info
+            .independence_list
+            .first()
+            .map(|independence| independence.lcd_list.clone())
+            .unwrap_or_default();
+
+        for screen in &mut screens {
+            let clock_id = clocks
+                .get(screen.lcd_index as usize)
+                .map(|lcd| lcd.lcd_clock_id)
+                .filter(|id| *id != PC_MONITOR_CLOCK_ID);
+            screen.clock_id = screen.clock_id.or(clock_id);
+        }
+    }
+
+    for screen in &mut screens {
+        let lcd = screen.lcd_index;
+        keep_image(&dir, &name, screen)
+            .await
+            .map_err(|e| format!("LCD {}: {}", lcd, e))?;
+    }
+
+    let scene = Scene {
+        name,
+        device_type: device_type(&ip_address).await,
+        brightness: settings.brightness,
+        screens,
+        pc_monitor_lcd,
+    };
+    write_scene(&dir, &scene)?;
+    Ok(scene)
+}
+
+/// Applies a saved scene, continuing past failed steps and reporting them together.
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn apply_scene(name: String, ip_address: String) -> Result<Scene, String> {
+    let scene = get_scene(name)?;
+
+    if let (Some(expected), Some(actual)) = (&scene.device_type, device_type(&ip_address).await) {
+        if expected != &actual {
+            return Err(format!(
+                "Scene '{}' was captured on a {}, {} is a {}",
+                scene.name, expected, ip_address, actual
+            ));
+        }
+    }
+
+    let mut errors = Vec::new();
+    if let Some(brightness) = scene.brightness {
+        if let Err(e) = device_commands::set_brightness(ip_address.clone(), brightness).await {
+            errors.push(e);
+        }
+    }
+
+    for screen in &scene.screens {
+        let lcd = screen.lcd_index;
+        if let Some(clock_id) = screen.clock_id {
+            if let Err(e) = channels::select_clock_face(ip_address.clone(), lcd, clock_id).await {
+                errors.push(format!("LCD {}: {}", lcd, e));
+            }
+        }
+        if let Some(source) = &screen.image {
+            let result = match draw_commands::load_image(source).await {
+                Ok(img) => draw_commands::send_image(&ip_address, &[lcd], img).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                errors.push(format!("LCD {}: {}", lcd, e));
+            }
+        }
+        if !screen.texts.is_empty() {
+            let items = screen
+                .texts
+                .iter()
+                .map(|text| DisplayItem {
+                    text: text.clone(),
+                    kind: DisplayItemKind::Text,
+                })
+                .collect();
+            if let Err(e) = draw_commands::set_screen_items(ip_address.clone(), lcd, items).await {
+                errors.push(format!("LCD {}: {}", lcd, e));
+            }
+        }
+    }
+
+    if let Some(lcd) = scene.pc_monitor_lcd {
+        if let Err(e) =
+            channels::select_clock_face(ip_address.clone(), lcd, PC_MONITOR_CLOCK_ID).await
+        {
+            errors.push(format!("LCD {}: {}", lcd, e));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(scene)
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scene(name: &str) -> Scene {
+        Scene {
+            name: name.to_string(),
+            device_type: None,
+            brightness: Some(50),
+            screens: Vec::new(),
+            pc_monitor_lcd: None,
+        }
+    }
+
+    #[test]
+    fn names_map_to_files_inside_the_folder() {
+        let dir = Path::new("scenes");
+
+        assert_eq!(scene_path(dir, "work").unwrap(), dir.join("work.json"));
+        assert_eq!(
+            scene_path(dir, "../night mode").unwrap(),
+            dir.join("___night_mode.json")
+        );
+        assert_eq!(
+            scene_path(dir, "a/b").unwrap(),
+            scene_path(dir, "a b").unwrap()
+        );
+        assert!(scene_path(dir, " ").is_err());
+    }
+
+    #[test]
+    fn colliding_names_do_not_overwrite_each_other() {
+        let dir = std::env::temp_dir().join(format!("scenes-test-{}", std::process::id()));
+
+        write_scene(&dir, &scene("a/b")).unwrap();
+        assert!(write_scene(&dir, &scene("a b")).is_err());
+        assert!(read_scene(&dir, "a b").is_err());
+
+        // Saving under the same name again replaces the scene
+        let brighter = Scene {
+            brightness: Some(90),
+            ..scene("a/b")
+        };
+        write_scene(&dir, &brighter).unwrap();
+        assert_eq!(read_scene(&dir, "a/b").unwrap().brightness, Some(90));
+        assert!(!dir.join("a_b.json.tmp").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,134 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use chrono::{Local, Offset};
+use serde::{Deserialize, Serialize};
+
+use crate::app_settings;
+use crate::divoom_api;
+use crate::runtime;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+// Set when the settings change, so new devices are synced without waiting a full interval
+static SYNC_REQUESTED: AtomicBool = AtomicBool::new(true);
+
+fn default_interval_hours() -> u32 {
+    6
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimeSyncSettings {
+    pub enabled: bool,
+    #[serde(default = "default_interval_hours")]
+    pub interval_hours: u32,
+    /// IP addresses of the devices to keep in sync.
+    #[serde(default)]
+    pub devices: Vec<String>,
+}
+
+impl Default for TimeSyncSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_hours: default_interval_hours(),
+            devices: Vec::new(),
+        }
+    }
+}
+
+fn host_offset_secs() -> i32 {
+    Local::now().offset().fix().local_minus_utc()
+}
+
+/// Formats a UTC offset the way `Sys/TimeZone` expects it: `GMT+3`, `GMT-9:30`.
+fn format_time_zone(offset_secs: i32) -> String {
+    let sign = if offset_secs < 0 { '-' } else { '+' };
+    let minutes = offset_secs.unsigned_abs() / 60;
+    match minutes % 60 {
+        0 => format!("GMT{}{}", sign, minutes / 60),
+        m => format!("GMT{}{}:{:02}", sign, minutes / 60, m),
+    }
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Sets the device's time zone and clock from the host.
+pub async fn sync_device(ip_address: &str) -> Result<(), String> {
+    let client = divoom_api::client(ip_address);
+    client
+        .set_time_zone(&format_time_zone(host_offset_secs()))
+        .await
+        .map_err(|e| format!("Failed to set time zone: {}", e))?;
+    client
+        .set_utc(unix_now())
+        .await
+        .map_err(|e| format!("Failed to set device time: {}", e))
+}
+
+/// Syncs again on the next check, e.g. after the device list changed.
+pub fn request_sync() {
+    SYNC_REQUESTED.store(true, Ordering::Relaxed);
+}
+
+/// Starts the background job that syncs the configured devices every
+/// `interval_hours` and whenever the host's UTC offset changes (DST).
+pub fn start() {
+    runtime::spawn(async {
+        let mut last_sync: Option<Instant> = None;
+        let mut last_offset = host_offset_secs();
+
+        loop {
+            let settings = app_settings::time_sync_settings();
+            let offset = host_offset_secs();
+            let interval = Duration::from_secs(u64::from(settings.interval_hours.max(1)) * 3600);
+
+            let due = SYNC_REQUESTED.swap(false, Ordering::Relaxed)
+                || offset != last_offset
+                || last_sync.is_none_or(|t| t.elapsed() >= interval);
+            last_offset = offset;
+
+            if settings.enabled && due {
+                for ip in &settings.devices {
+                    if let Err(e) = sync_device(ip).await {
+                        eprintln!("[Time sync] {}: {}", ip, e);
+                    }
+                }
+                last_sync = Some(Instant::now());
+            }
+
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_host_time_zone() -> String {
+    format_time_zone(host_offset_secs())
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn set_device_time_zone(ip_address: String, time_zone: String) -> Result<(), String> {
+    divoom_api::client(&ip_address)
+        .set_time_zone(&time_zone)
+        .await
+        .map_err(|e| format!("Failed to set time zone: {}", e))
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn set_device_utc(ip_address: String, utc: i64) -> Result<(), String> {
+    divoom_api::client(&ip_address)
+        .set_utc(utc)
+        .await
+        .map_err(|e| format!("Failed to set device time: {}", e))
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn sync_device_time(ip_address: String) -> Result<(), String> {
+    sync_device(&ip_address).await
+}
@@ -0,0 +1,203 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use divoom::{StopwatchAction, MAX_SCORE};
+use serde::{Deserialize, Serialize};
+
+use crate::divoom_api;
+
+// The firmware does not report tool state, so it is tracked from the commands we send
+static TOOL_STATES: Mutex<BTreeMap<String, ToolState>> = Mutex::new(BTreeMap::new());
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Tool {
+    Timer,
+    Stopwatch,
+    Scoreboard,
+    NoiseMeter,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Team {
+    Red,
+    Blue,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TimerState {
+    pub running: bool,
+    pub duration_secs: u32,
+    /// Unix time the countdown ends, while running.
+    pub ends_at: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StopwatchState {
+    pub running: bool,
+    /// Seconds counted before the current run.
+    pub elapsed_secs: u64,
+    /// Unix time of the current run's start.
+    pub started_at: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct ScoreboardState {
+    pub red: u16,
+    pub blue: u16,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ToolState {
+    /// Tool last shown on the device.
+    pub active: Option<Tool>,
+    pub timer: TimerState,
+    pub stopwatch: StopwatchState,
+    pub scoreboard: ScoreboardState,
+    pub noise_meter: bool,
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn tool_state(ip_address: &str) -> ToolState {
+    let mut state = TOOL_STATES
+        .lock()
+        .ok()
+        .and_then(|states| states.get(ip_address).cloned())
+        .unwrap_or_default();
+
+    if state.timer.ends_at.is_some_and(|end| end <= unix_now()) {
+        state.timer.running = false;
+        state.timer.ends_at = None;
+    }
+
+    state
+}
+
+fn update_state(ip_address: &str, tool: Tool, update: impl FnOnce(&mut ToolState)) -> ToolState {
+    let mut state = tool_state(ip_address);
+    state.active = Some(tool);
+    update(&mut state);
+
+    if let Ok(mut states) = TOOL_STATES.lock() {
+        states.insert(ip_address.to_string(), state.clone());
+    }
+    state
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_tool_state(ip_address: String) -> ToolState {
+    tool_state(&ip_address)
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn start_timer(
+    ip_address: String,
+    minutes: u32,
+    seconds: u32,
+) -> Result<ToolState, String> {
+    let duration = minutes
+        .checked_mul(60)
+        .and_then(|secs| secs.checked_add(seconds))
+        .ok_or_else(|| format!("Timer of {}:{:02} is too long", minutes, seconds))?;
+
+    divoom_api::client(&ip_address)
+        .set_timer(minutes, seconds, true)
+        .await
+        .map_err(|e| format!("Failed to start timer: {}", e))?;
+
+    Ok(update_state(&ip_address, Tool::Timer, |state| {
+        state.timer = TimerState {
+            running: true,
+            duration_secs: duration,
+            ends_at: Some(unix_now() + u64::from(duration)),
+        };
+    }))
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn stop_timer(ip_address: String) -> Result<ToolState, String> {
+    divoom_api::client(&ip_address)
+        .set_timer(0, 0, false)
+        .await
+        .map_err(|e| format!("Failed to stop timer: {}", e))?;
+
+    Ok(update_state(&ip_address, Tool::Timer, |state| {
+        state.timer.running = false;
+        state.timer.ends_at = None;
+    }))
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn control_stopwatch(
+    ip_address: String,
+    action: StopwatchAction,
+) -> Result<ToolState, String> {
+    divoom_api::client(&ip_address)
+        .set_stopwatch(action)
+        .await
+        .map_err(|e| format!("Failed to control stopwatch: {}", e))?;
+
+    Ok(update_state(&ip_address, Tool::Stopwatch, |state| {
+        let now = unix_now();
+        let stopwatch = &mut state.stopwatch;
+        match action {
+            StopwatchAction::Start if !stopwatch.running => {
+                stopwatch.running = true;
+                stopwatch.started_at = Some(now);
+            }
+            StopwatchAction::Start => {}
+            StopwatchAction::Stop => {
+                if let Some(started_at) = stopwatch.started_at.take() {
+                    stopwatch.elapsed_secs += now.saturating_sub(started_at);
+                }
+                stopwatch.running = false;
+            }
+            StopwatchAction::Reset => *stopwatch = StopwatchState::default(),
+        }
+    }))
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn set_scoreboard(ip_address: String, red: u16, blue: u16) -> Result<ToolState, String> {
+    divoom_api::client(&ip_address)
+        .set_scoreboard(red, blue)
+        .await
+        .map_err(|e| format!("Failed to set scoreboard: {}", e))?;
+
+    Ok(update_state(&ip_address, Tool::Scoreboard, |state| {
+        state.scoreboard = ScoreboardState { red, blue };
+    }))
+}
+
+/// Adds `delta` (may be negative) to one team's score, clamped to the board's range.
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn change_score(ip_address: String, team: Team, delta: i32) -> Result<ToolState, String> {
+    let ScoreboardState { mut red, mut blue } = tool_state(&ip_address).scoreboard;
+    let score = match team {
+        Team::Red => &mut red,
+        Team::Blue => &mut blue,
+    };
+    *score = (i32::from(*score) + delta).clamp(0, i32::from(MAX_SCORE)) as u16;
+
+    set_scoreboard(ip_address, red, blue).await
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn set_noise_meter(ip_address: String, enabled: bool) -> Result<ToolState, String> {
+    divoom_api::client(&ip_address)
+        .set_noise_meter(enabled)
+        .await
+        .map_err(|e| format!("Failed to control noise meter: {}", e))?;
+
+    Ok(update_state(&ip_address, Tool::NoiseMeter, |state| {
+        state.noise_meter = enabled;
+    }))
+}
@@ -0,0 +1,181 @@
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use divoom::protocol::WeatherInfo;
+use serde::{Deserialize, Serialize};
+
+use crate::app_settings;
+use crate::device_commands;
+use crate::divoom_api;
+use crate::draw_commands;
+use crate::metric_pipeline;
+use crate::metric_providers;
+use crate::models::{Color, TextConfig, WEATHER_TEXT_ID};
+use crate::runtime;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+fn default_text_id() -> u8 {
+    WEATHER_TEXT_ID
+}
+
+fn default_interval_secs() -> u64 {
+    60
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Location {
+    pub longitude: f64,
+    pub latitude: f64,
+}
+
+/// Shows a locally measured temperature on a device.
+///
+/// The firmware has no local command to override its own weather data, so the
+/// reading is drawn as a text element next to the clock face.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LocalTemperaturePush {
+    pub enabled: bool,
+    /// Metric name understood by the pipeline, usually a custom provider slot.
+    pub metric: String,
+    pub ip_address: String,
+    pub lcd_index: u32,
+    #[serde(default = "default_text_id")]
+    pub text_id: u8,
+    #[serde(default)]
+    pub x: u8,
+    #[serde(default)]
+    pub y: u8,
+    #[serde(default)]
+    pub color: Option<Color>,
+    #[serde(default = "default_interval_secs")]
+    pub interval_secs: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WeatherSettings {
+    /// Applied to every device in `devices` when the settings are saved.
+    #[serde(default)]
+    pub location: Option<Location>,
+    #[serde(default)]
+    pub devices: Vec<String>,
+    #[serde(default)]
+    pub local_temperature: Vec<LocalTemperaturePush>,
+}
+
+/// Sends the configured location to all configured devices in the background.
+pub fn apply_location(settings: &WeatherSettings) {
+    let Some(location) = settings.location else {
+        return;
+    };
+
+    for ip in settings.devices.clone() {
+        runtime::spawn(async move {
+            if let Err(e) = divoom_api::client(&ip)
+                .set_location(location.longitude, location.latitude)
+                .await
+            {
+                eprintln!("[Weather] Failed to set location on {}: {}", ip, e);
+            }
+        });
+    }
+}
+
+async fn push_temperature(push: &LocalTemperaturePush) -> Result<(), String> {
+    let metrics = metric_providers::collect_metrics().await;
+    // Whole degrees unless the pipeline asks for more, as on the PC Monitor face
+    let fahrenheit = device_commands::temperature_mode(&push.ip_address).await == 1;
+    let mut pipelines = metric_pipeline::device_pipelines(&push.ip_address, fahrenheit);
+    pipelines
+        .entry(push.metric.clone())
+        .or_default()
+        .precision
+        .get_or_insert(0);
+
+    let processed = metric_pipeline::process(
+        &metric_pipeline::lcd_consumer(&push.ip_address, push.lcd_index, "temperature"),
+        &metrics,
+        &pipelines,
+    );
+    let value = processed
+        .get(&push.metric)
+        .ok_or_else(|| format!("Metric '{}' is not available", push.metric))?;
+
+    // Up to the right edge, so the text fits panels smaller than the Times Gate's
+    let resolution = device_commands::resolution(&push.ip_address).await;
+    let width = resolution
+        .saturating_sub(u32::from(push.x))
+        .min(u32::from(TextConfig::DEFAULT_WIDTH)) as u8;
+
+    draw_commands::set_screen_text(
+        push.ip_address.clone(),
+        push.lcd_index,
+        TextConfig {
+            id: push.text_id,
+            content: format!("{}{}", value.text, value.unit),
+            x: push.x,
+            y: push.y,
+            font: None,
+            color: push.color,
+            alignment: None,
+            text_width: Some(width),
+            direction: None,
+            speed: None,
+        },
+    )
+    .await
+}
+
+/// Starts the background job that pushes local temperature readings.
+pub fn start() {
+    runtime::spawn(async {
+        let mut last_pushed: BTreeMap<(String, u32, u8), Instant> = BTreeMap::new();
+
+        loop {
+            let pushes = app_settings::weather_settings().local_temperature;
+            last_pushed.retain(|(ip, lcd, id), _| {
+                pushes
+                    .iter()
+                    .any(|p| &p.ip_address == ip && p.lcd_index == *lcd && p.text_id == *id)
+            });
+
+            for push in pushes.iter().filter(|p| p.enabled) {
+                let key = (push.ip_address.clone(), push.lcd_index, push.text_id);
+                let interval = Duration::from_secs(push.interval_secs.max(1));
+                if last_pushed
+                    .get(&key)
+                    .is_some_and(|t| t.elapsed() < interval)
+                {
+                    continue;
+                }
+
+                if let Err(e) = push_temperature(push).await {
+                    eprintln!("[Weather] {}: {}", push.ip_address, e);
+                }
+                last_pushed.insert(key, Instant::now());
+            }
+
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn set_device_location(
+    ip_address: String,
+    longitude: f64,
+    latitude: f64,
+) -> Result<(), String> {
+    divoom_api::client(&ip_address)
+        .set_location(longitude, latitude)
+        .await
+        .map_err(|e| format!("Failed to set location: {}", e))
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn get_device_weather(ip_address: String) -> Result<WeatherInfo, String> {
+    divoom_api::client(&ip_address)
+        .get_weather()
+        .await
+        .map_err(|e| format!("Failed to read weather: {}", e))
+}
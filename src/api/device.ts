@@ -0,0 +1,400 @@
+import { invoke } from '@tauri-apps/api/core';
+
+import type {
+  AutomationJob,
+  BrightnessSchedule,
+  BuzzerPattern,
+  BuzzerTiming,
+  Channel,
+  ChannelSelection,
+  ClockFacePage,
+  CloudChannel,
+  DeviceGroup,
+  DeviceResult,
+  DisplayItem,
+  JobRun,
+  Playlist,
+  PlaylistStatus,
+  Scene,
+  SceneScreen,
+  StopwatchAction,
+  SunTimes,
+  Team,
+  TextConfig,
+  TimeSyncSettings,
+  ToolState,
+  TrayChannelSettings,
+  WeatherInfo,
+  WeatherSettings,
+} from '../types/device';
+
+export const getHostTimeZone = async (): Promise<string> => {
+  return invoke<string>('get_host_time_zone');
+};
+
+export const setDeviceTimeZone = async (
+  ipAddress: string,
+  timeZone: string,
+): Promise<void> => {
+  return invoke('set_device_time_zone', { ipAddress, timeZone });
+};
+
+export const setDeviceUtc = async (
+  ipAddress: string,
+  utc: number,
+): Promise<void> => {
+  return invoke('set_device_utc', { ipAddress, utc });
+};
+
+export const syncDeviceTime = async (ipAddress: string): Promise<void> => {
+  return invoke('sync_device_time', { ipAddress });
+};
+
+export const getTimeSyncSettings = async (): Promise<TimeSyncSettings> => {
+  return invoke<TimeSyncSettings>('get_time_sync_settings');
+};
+
+export const setTimeSyncSettings = async (
+  settings: TimeSyncSettings,
+): Promise<void> => {
+  return invoke('set_time_sync_settings', { settings });
+};
+
+export const setDeviceLocation = async (
+  ipAddress: string,
+  longitude: number,
+  latitude: number,
+): Promise<void> => {
+  return invoke('set_device_location', { ipAddress, longitude, latitude });
+};
+
+export const getDeviceWeather = async (
+  ipAddress: string,
+): Promise<WeatherInfo> => {
+  return invoke<WeatherInfo>('get_device_weather', { ipAddress });
+};
+
+export const getWeatherSettings = async (): Promise<WeatherSettings> => {
+  return invoke<WeatherSettings>('get_weather_settings');
+};
+
+export const setWeatherSettings = async (
+  settings: WeatherSettings,
+): Promise<void> => {
+  return invoke('set_weather_settings', { settings });
+};
+
+export const getToolState = async (ipAddress: string): Promise<ToolState> => {
+  return invoke<ToolState>('get_tool_state', { ipAddress });
+};
+
+export const startTimer = async (
+  ipAddress: string,
+  minutes: number,
+  seconds: number,
+): Promise<ToolState> => {
+  return invoke<ToolState>('start_timer', { ipAddress, minutes, seconds });
+};
+
+export const stopTimer = async (ipAddress: string): Promise<ToolState> => {
+  return invoke<ToolState>('stop_timer', { ipAddress });
+};
+
+export const controlStopwatch = async (
+  ipAddress: string,
+  action: StopwatchAction,
+): Promise<ToolState> => {
+  return invoke<ToolState>('control_stopwatch', { ipAddress, action });
+};
+
+export const setScoreboard = async (
+  ipAddress: string,
+  red: number,
+  blue: number,
+): Promise<ToolState> => {
+  return invoke<ToolState>('set_scoreboard', { ipAddress, red, blue });
+};
+
+export const changeScore = async (
+  ipAddress: string,
+  team: Team,
+  delta: number,
+): Promise<ToolState> => {
+  return invoke<ToolState>('change_score', { ipAddress, team, delta });
+};
+
+export const setNoiseMeter = async (
+  ipAddress: string,
+  enabled: boolean,
+): Promise<ToolState> => {
+  return invoke<ToolState>('set_noise_meter', { ipAddress, enabled });
+};
+
+export const playBuzzer = async (
+  ipAddress: string,
+  timing: BuzzerTiming,
+): Promise<void> => {
+  return invoke('play_buzzer', { ipAddress, timing });
+};
+
+export const playBuzzerPattern = async (
+  ipAddress: string,
+  pattern: BuzzerPattern,
+): Promise<void> => {
+  return invoke('play_buzzer_pattern', { ipAddress, pattern });
+};
+
+export const setChannel = async (
+  ipAddress: string,
+  channel: Channel,
+): Promise<void> => {
+  return invoke('set_channel', { ipAddress, channel });
+};
+
+export const getChannelSelection = async (
+  ipAddress: string,
+): Promise<ChannelSelection> => {
+  return invoke<ChannelSelection>('get_channel_selection', { ipAddress });
+};
+
+export const setCustomPage = async (
+  ipAddress: string,
+  index: number,
+): Promise<void> => {
+  return invoke('set_custom_page', { ipAddress, index });
+};
+
+export const setVisualizer = async (
+  ipAddress: string,
+  position: number,
+): Promise<void> => {
+  return invoke('set_visualizer', { ipAddress, position });
+};
+
+export const setCloudChannel = async (
+  ipAddress: string,
+  channel: CloudChannel,
+): Promise<void> => {
+  return invoke('set_cloud_channel', { ipAddress, channel });
+};
+
+export const selectClockFace = async (
+  ipAddress: string,
+  lcdIndex: number,
+  clockId: number,
+): Promise<void> => {
+  return invoke('select_clock_face', { ipAddress, lcdIndex, clockId });
+};
+
+export const getClockFaceTypes = async (): Promise<string[]> => {
+  return invoke<string[]>('get_clock_face_types');
+};
+
+export const getClockFaces = async (
+  dialType: string,
+  page: number,
+): Promise<ClockFacePage> => {
+  return invoke<ClockFacePage>('get_clock_faces', { dialType, page });
+};
+
+export const getTrayChannelSettings =
+  async (): Promise<TrayChannelSettings | null> => {
+    return invoke<TrayChannelSettings | null>('get_tray_channel_settings');
+  };
+
+export const setTrayChannelSettings = async (
+  settings: TrayChannelSettings | null,
+): Promise<void> => {
+  return invoke('set_tray_channel_settings', { settings });
+};
+
+export const getBrightnessSchedule = async (): Promise<BrightnessSchedule> => {
+  return invoke<BrightnessSchedule>('get_brightness_schedule');
+};
+
+export const setBrightnessSchedule = async (
+  schedule: BrightnessSchedule,
+): Promise<void> => {
+  return invoke('set_brightness_schedule', { schedule });
+};
+
+export const getSunTimes = async (): Promise<SunTimes | null> => {
+  return invoke<SunTimes | null>('get_sun_times');
+};
+
+export const getAutomationJobs = async (): Promise<AutomationJob[]> => {
+  return invoke<AutomationJob[]>('get_automation_jobs');
+};
+
+export const setAutomationJobs = async (
+  jobs: AutomationJob[],
+): Promise<void> => {
+  return invoke('set_automation_jobs', { jobs });
+};
+
+export const runAutomationJob = async (jobId: string): Promise<JobRun> => {
+  return invoke<JobRun>('run_automation_job', { jobId });
+};
+
+export const getAutomationHistory = async (
+  jobId?: string,
+): Promise<JobRun[]> => {
+  return invoke<JobRun[]>('get_automation_history', { jobId: jobId ?? null });
+};
+
+export const getPlaylists = async (): Promise<Playlist[]> => {
+  return invoke<Playlist[]>('get_playlists');
+};
+
+export const setPlaylists = async (playlists: Playlist[]): Promise<void> => {
+  return invoke('set_playlists', { playlists });
+};
+
+export const setPlaylistPaused = async (
+  playlistId: string,
+  paused: boolean,
+): Promise<void> => {
+  return invoke('set_playlist_paused', { playlistId, paused });
+};
+
+export const skipPlaylistEntry = async (playlistId: string): Promise<void> => {
+  return invoke('skip_playlist_entry', { playlistId });
+};
+
+export const getPlaylistStatus = async (): Promise<PlaylistStatus[]> => {
+  return invoke<PlaylistStatus[]>('get_playlist_status');
+};
+
+export const listScenes = async (): Promise<Scene[]> => {
+  return invoke<Scene[]>('list_scenes');
+};
+
+export const getScene = async (name: string): Promise<Scene> => {
+  return invoke<Scene>('get_scene', { name });
+};
+
+export const saveScene = async (scene: Scene): Promise<void> => {
+  return invoke('save_scene', { scene });
+};
+
+export const deleteScene = async (name: string): Promise<void> => {
+  return invoke('delete_scene', { name });
+};
+
+export const captureScene = async (
+  name: string,
+  ipAddress: string,
+  screens: SceneScreen[],
+  pcMonitorLcd: number | null,
+): Promise<Scene> => {
+  return invoke<Scene>('capture_scene', {
+    name,
+    ipAddress,
+    screens,
+    pcMonitorLcd,
+  });
+};
+
+/** Resolves with the applied scene; rejects with every failed step. */
+export const applyScene = async (
+  name: string,
+  ipAddress: string,
+): Promise<Scene> => {
+  return invoke<Scene>('apply_scene', { name, ipAddress });
+};
+
+export const getDeviceGroups = async (): Promise<DeviceGroup[]> => {
+  return invoke<DeviceGroup[]>('get_device_groups');
+};
+
+export const setDeviceGroups = async (
+  groups: DeviceGroup[],
+): Promise<void> => {
+  return invoke('set_device_groups', { groups });
+};
+
+export const groupSetBrightness = async (
+  groupId: string,
+  value: number,
+): Promise<DeviceResult[]> => {
+  return invoke<DeviceResult[]>('group_set_brightness', { groupId, value });
+};
+
+export const groupSetScreen = async (
+  groupId: string,
+  on: boolean,
+): Promise<DeviceResult[]> => {
+  return invoke<DeviceResult[]>('group_set_screen', { groupId, on });
+};
+
+export const groupSetText = async (
+  groupId: string,
+  screenIndex: number,
+  textConfig: TextConfig,
+): Promise<DeviceResult[]> => {
+  return invoke<DeviceResult[]>('group_set_text', {
+    groupId,
+    screenIndex,
+    textConfig,
+  });
+};
+
+/** `source` is an http(s) URL or a local file path. */
+export const groupUploadImage = async (
+  groupId: string,
+  screenIndexes: number[],
+  source: string,
+): Promise<DeviceResult[]> => {
+  return invoke<DeviceResult[]>('group_upload_image', {
+    groupId,
+    screenIndexes,
+    source,
+  });
+};
+
+export const groupSendSystemMetrics = async (
+  groupId: string,
+  lcdIndex: number,
+): Promise<DeviceResult[]> => {
+  return invoke<DeviceResult[]>('group_send_system_metrics', {
+    groupId,
+    lcdIndex,
+  });
+};
+
+/**
+ * Spreads an image or GIF across all LCDs; `bezelPx` is the gap between two
+ * LCDs in pixels, skipped so lines stay straight.
+ */
+export const uploadPanorama = async (
+  ipAddress: string,
+  source: string,
+  bezelPx?: number,
+): Promise<void> => {
+  return invoke('upload_panorama', {
+    ipAddress,
+    source,
+    bezelPx: bezelPx ?? null,
+  });
+};
+
+/** Starts the device's PicID sequence over, e.g. when uploads show an old picture. */
+export const resetPicId = async (ipAddress: string): Promise<void> => {
+  return invoke('reset_pic_id', { ipAddress });
+};
+
+/** Sends all items in one request, so the whole layout appears at once. */
+export const setScreenItems = async (
+  ipAddress: string,
+  screenIndex: number,
+  items: DisplayItem[],
+): Promise<void> => {
+  return invoke('set_screen_items', { ipAddress, screenIndex, items });
+};
+
+export const clearScreenText = async (
+  ipAddress: string,
+  screenIndex: number,
+): Promise<void> => {
+  return invoke('clear_screen_text', { ipAddress, screenIndex });
+};
@@ -1,11 +1,66 @@
 import { invoke } from '@tauri-apps/api/core';
 
-import type { LcdInfoResponse, SystemMetrics } from '../types/system';
+import type {
+  AlertRule,
+  LcdInfoResponse,
+  MetricPipelines,
+  MetricProviderConfig,
+  ProcessedMetrics,
+  PrometheusSettings,
+  RestApiSettings,
+  SensorInfo,
+  SensorMapping,
+  SystemMetrics,
+} from '../types/system';
 
 export const getSystemMetrics = async (): Promise<SystemMetrics> => {
   return invoke<SystemMetrics>('get_system_metrics');
 };
 
+export const getProcessedMetrics = async (
+  target: string,
+): Promise<ProcessedMetrics> => {
+  return invoke<ProcessedMetrics>('get_processed_metrics', { target });
+};
+
+export const getMetricPipelines = async (): Promise<MetricPipelines> => {
+  return invoke<MetricPipelines>('get_metric_pipelines');
+};
+
+export const setMetricPipelines = async (
+  pipelines: MetricPipelines,
+): Promise<void> => {
+  return invoke('set_metric_pipelines', { pipelines });
+};
+
+export const getMetricProviders = async (): Promise<MetricProviderConfig[]> => {
+  return invoke<MetricProviderConfig[]>('get_metric_providers');
+};
+
+export const setMetricProviders = async (
+  providers: MetricProviderConfig[],
+): Promise<void> => {
+  return invoke('set_metric_providers', { providers });
+};
+
+export const testMetricProvider = async (
+  config: MetricProviderConfig,
+): Promise<number> => {
+  return invoke<number>('test_metric_provider', { config });
+};
+
+export const getSensorCatalog = async (): Promise<SensorInfo[]> => {
+  return invoke<SensorInfo[]>('get_sensor_catalog');
+};
+
+export const getSensorMapping = async (): Promise<SensorMapping> => {
+  return invoke<SensorMapping>('get_sensor_mapping');
+};
+
+export const setSensorMapping = async (mapping: SensorMapping): Promise<void> => {
+  return invoke('set_sensor_mapping', { mapping });
+};
+
 export const getLcdInfo = async (ipAddress: string): Promise<LcdInfoResponse> => {
   return invoke<LcdInfoResponse>('get_lcd_info', { ipAddress });
 };
@@ -31,3 +86,42 @@ export const sendPcMetrics = async (
 ): Promise<void> => {
   return invoke('send_pc_metrics', { ipAddress, lcdIndex, dispData });
 };
+
+export const sendSystemMetrics = async (
+  ipAddress: string,
+  lcdIndex: number,
+): Promise<void> => {
+  return invoke('send_system_metrics', { ipAddress, lcdIndex });
+};
+
+export const getAlertRules = async (): Promise<AlertRule[]> => {
+  return invoke<AlertRule[]>('get_alert_rules');
+};
+
+export const setAlertRules = async (rules: AlertRule[]): Promise<void> => {
+  return invoke('set_alert_rules', { rules });
+};
+
+export const testAlertRule = async (rule: AlertRule): Promise<void> => {
+  return invoke('test_alert_rule', { rule });
+};
+
+export const getPrometheusSettings = async (): Promise<PrometheusSettings> => {
+  return invoke<PrometheusSettings>('get_prometheus_settings');
+};
+
+export const setPrometheusSettings = async (
+  settings: PrometheusSettings,
+): Promise<void> => {
+  return invoke('set_prometheus_settings', { settings });
+};
+
+export const getRestApiSettings = async (): Promise<RestApiSettings> => {
+  return invoke<RestApiSettings>('get_rest_api_settings');
+};
+
+export const setRestApiSettings = async (
+  settings: RestApiSettings,
+): Promise<void> => {
+  return invoke('set_rest_api_settings', { settings });
+};
@@ -0,0 +1,120 @@
+import { applyScene, captureScene } from '../api/device';
+import { FONT_OPTIONS, TEXT_ALIGNMENT_OPTIONS } from '../constants';
+import type { Scene, SceneScreen } from '../types/device';
+import type { ScreenConfig, ScreenConfigs } from '../types/screen';
+import {
+  loadPcMonitorSettings,
+  savePcMonitorSettings,
+  startPcMonitorLoop,
+  stopPcMonitorLoop,
+} from './usePcMonitorSend';
+
+/** Editor number of a font or alignment the backend returned by name */
+function toEditorValue<T extends string>(
+  value: T | number | null,
+  options: { value: number; name: T }[]
+): number | undefined {
+  if (typeof value === 'string') {
+    return options.find((option) => option.name === value)?.value;
+  }
+  return value ?? undefined;
+}
+
+function toSceneScreen(config: ScreenConfig): SceneScreen {
+  return {
+    lcd_index: config.screenIndex,
+    clock_id: null,
+    image: config.image?.source ?? null,
+    texts: config.texts.map((text) => ({
+      id: text.id,
+      content: text.content,
+      x: text.x,
+      y: text.y,
+      font: text.font ?? null,
+      color: text.color?.toUpperCase() ?? null,
+      alignment: text.alignment ?? null,
+      text_width: text.textWidth,
+    })),
+  };
+}
+
+function toScreenConfig(screen: SceneScreen): ScreenConfig {
+  const image = screen.image
+    ? {
+        type: /^https?:\/\//i.test(screen.image)
+          ? ('url' as const)
+          : ('local' as const),
+        source: screen.image,
+      }
+    : undefined;
+
+  return {
+    screenIndex: screen.lcd_index,
+    image,
+    texts: screen.texts.map((text) => ({
+      id: text.id,
+      content: text.content,
+      x: text.x,
+      y: text.y,
+      font: toEditorValue(text.font, FONT_OPTIONS),
+      color: text.color ?? undefined,
+      alignment: toEditorValue(text.alignment, TEXT_ALIGNMENT_OPTIONS),
+      textWidth: text.text_width ?? 64,
+    })),
+    textIds: screen.texts.map((text) => text.id),
+  };
+}
+
+/**
+ * Saves the device's screen configs and PC Monitor state as a named scene
+ */
+export async function captureSceneFromConfigs(
+  name: string,
+  deviceIp: string,
+  screenConfigs: ScreenConfigs
+): Promise<Scene> {
+  const screens = Object.values(screenConfigs).map(toSceneScreen);
+  const pcMonitor = loadPcMonitorSettings(deviceIp);
+
+  return captureScene(
+    name,
+    deviceIp,
+    screens,
+    pcMonitor?.enabled ? pcMonitor.lcdIndex : null
+  );
+}
+
+/**
+ * Applies a scene and makes the editor and the PC Monitor loop follow it
+ */
+export async function applySceneToDevice(
+  name: string,
+  deviceIp: string
+): Promise<Scene> {
+  const scene = await applyScene(name, deviceIp);
+
+  const screenConfigs: ScreenConfigs = {};
+  for (const screen of scene.screens) {
+    screenConfigs[screen.lcd_index] = toScreenConfig(screen);
+  }
+  localStorage.setItem(
+    `screen_configs_${encodeURIComponent(deviceIp)}`,
+    JSON.stringify(screenConfigs)
+  );
+
+  if (scene.pc_monitor_lcd !== null) {
+    savePcMonitorSettings(deviceIp, {
+      lcdIndex: scene.pc_monitor_lcd,
+      enabled: true,
+    });
+    startPcMonitorLoop(deviceIp, scene.pc_monitor_lcd);
+  } else {
+    const previous = loadPcMonitorSettings(deviceIp);
+    if (previous) {
+      savePcMonitorSettings(deviceIp, { ...previous, enabled: false });
+    }
+    stopPcMonitorLoop(deviceIp);
+  }
+
+  return scene;
+}
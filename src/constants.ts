@@ -1,3 +1,5 @@
+import type { Font, TextAlignment } from './types/device';
+
 export const dateFormats = [
   {
     value: 'yyyy-mm-dd',
@@ -25,27 +27,33 @@ export const dateFormats = [
   },
 ];
 
-export const TEXT_ALIGNMENT_OPTIONS: { label: string; value: number }[] = [
-  { label: 'Scroll', value: 0 },
-  { label: 'Normal', value: 1 },
-  { label: 'Middle', value: 2 },
-  { label: 'Right', value: 3 },
-  { label: 'Left', value: 4 },
+export const TEXT_ALIGNMENT_OPTIONS: {
+  label: string;
+  value: number;
+  name: TextAlignment;
+}[] = [
+  { label: 'Scroll', value: 0, name: 'scroll' },
+  { label: 'Normal', value: 1, name: 'normal' },
+  { label: 'Middle', value: 2, name: 'middle' },
+  { label: 'Right', value: 3, name: 'right' },
+  { label: 'Left', value: 4, name: 'left' },
 ];
 
-export const FONT_OPTIONS: { label: string; value: number }[] = [
-  { label: 'Arial', value: 0 },
-  { label: 'Times New Roman', value: 1 },
-  { label: 'Verdana', value: 2 },
-  { label: 'Courier New', value: 3 },
-  { label: 'Georgia', value: 4 },
-  { label: 'Garamond', value: 5 },
-  { label: 'Comic Sans MS', value: 6 },
-  { label: 'Impact', value: 7 },
+export const FONT_OPTIONS: { label: string; value: number; name: Font }[] = [
+  { label: 'Arial', value: 0, name: 'arial' },
+  { label: 'Times New Roman', value: 1, name: 'times_new_roman' },
+  { label: 'Verdana', value: 2, name: 'verdana' },
+  { label: 'Courier New', value: 3, name: 'courier_new' },
+  { label: 'Georgia', value: 4, name: 'georgia' },
+  { label: 'Garamond', value: 5, name: 'garamond' },
+  { label: 'Comic Sans MS', value: 6, name: 'comic_sans_ms' },
+  { label: 'Impact', value: 7, name: 'impact' },
 ];
 
+// 19 and 20 are reserved for the local temperature and alert texts, see
+// WEATHER_TEXT_ID and ALERT_TEXT_ID in src-tauri/src/models.rs.
 export const TEXT_IDS: number[] = [
-  0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20,
+  0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18,
 ];
 
 export const commands = [
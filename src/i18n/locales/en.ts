@@ -1,158 +1,178 @@
-export default {
-  app: {
-    title: 'Divoom Device Monitor',
-    routeNotFound: 'Route not found: {path}',
-  },
-  language: {
-    ru: 'Русский',
-    en: 'English',
-  },
-  header: {
-    appSettings: 'App Settings',
-  },
-  appSettings: {
-    title: 'App Settings',
-    appearance: 'Appearance',
-    appTheme: 'App Theme',
-    dark: 'Dark',
-    light: 'Light',
-    switchToLight: 'Switch to light theme',
-    switchToDark: 'Switch to dark theme',
-    behavior: 'Behavior',
-    autostart: 'Launch at Windows startup',
-    autostartDescription: 'The app will start automatically when you log in',
-    closeToTray: 'Minimize to tray on close',
-    closeToTrayDescription: 'The app will minimize to the system tray instead of closing completely',
-    language: 'Language',
-    languageLabel: 'App language',
-    updates: 'Updates',
-    currentVersion: 'Current version',
-    checkForUpdates: 'Check for updates',
-    checking: 'Checking...',
-    updateAvailable: 'Version {version} available',
-    noUpdates: 'You are on the latest version',
-    downloading: 'Downloading update...',
-    downloadProgress: 'Downloaded {downloaded} of {total}',
-    installAndRestart: 'Install and restart',
-    updateError: 'Failed to check for updates',
-    releaseNotes: 'Release notes',
-  },
-  deviceList: {
-    scanning: 'Scanning...',
-    scanDevices: 'Scan Devices',
-    scanFailed: 'Failed to scan devices',
-    noDevices: "No devices found. Click 'Scan Devices' to search.",
-  },
-  deviceCard: {
-    connected: 'Connected',
-    disconnected: 'Disconnected',
-    deviceType: 'Device Type',
-    ipAddress: 'IP Address',
-    macAddress: 'MAC Address',
-    id: 'ID',
-    signalStrength: 'Signal Strength',
-  },
-  settingsLayout: {
-    settings: 'Settings',
-    commonSettings: 'General Settings',
-    screenSettings: 'Screen Settings',
-    systemStatus: 'System Status',
-    deviceSettings: 'Device Settings',
-  },
-  deviceSettings: {
-    loading: 'Loading...',
-  },
-  displaySettings: {
-    timesGateOnly: 'Screen settings are only available for Times Gate devices',
-  },
-  commonSettings: {
-    currentSettings: 'Current Settings',
-    refreshSettings: 'Refresh settings',
-    rebootDevice: 'Reboot device',
-    basicSettings: 'Basic Settings',
-    powerToggle: 'Power on/off',
-    brightness: 'Brightness',
-    mirror: 'Mirror',
-    temperatureFormat: 'Temperature Format',
-    celsius: 'Celsius',
-    fahrenheit: 'Fahrenheit',
-    timeFormat24: '24-hour format',
-    noSettings: "Settings not loaded. Click 'Refresh' to load.",
-    invalidIp: 'Invalid IP address format',
-    loadError: 'Failed to load device settings',
-  },
-  systemMetrics: {
-    title: 'System Status',
-    subtitle: 'Real-time load and temperature metrics',
-    updated: 'Updated: {time}',
-    refresh: 'Refresh',
-    cpu: 'CPU',
-    gpu: 'GPU',
-    ram: 'RAM',
-    load: 'Load',
-    temperature: 'Temperature',
-    gpuHint: 'GPU data is unavailable. Make sure the sidecar is running with admin privileges.',
-    usage: 'Usage',
-    of: 'of',
-    disks: 'Disks',
-    disksUnavailable: 'Disk data is unavailable.',
-    disk: 'Disk',
-    sendToDevice: 'Send to Device',
-    screen: 'Screen',
-    screenN: 'Screen {n}',
-    screenPcMonitor: 'Screen {n} (PC Monitor)',
-    screenClock: 'Screen {n} (Clock: {clockId})',
-    pcMonitorMode: 'PC Monitor Mode',
-    alreadyActivated: 'Already activated',
-    activate: 'Activate',
-    autoSendMetrics: 'Auto-send metrics',
-    on: 'On',
-    off: 'Off',
-    metricsSending: 'Metrics are being sent in the background every 2 sec.',
-    sentData: 'Data being sent:',
-    pcMonitorActivated: 'PC Monitor activated on screen {n}',
-    activationError: 'Activation error: {error}',
-    metricsError: 'Failed to get system metrics',
-    lcdError: 'Failed to get screen information',
-  },
-  screenEditor: {
-    image: 'Image',
-    loadFromComputer: 'Load from computer',
-    imageUrl: 'Image URL',
-    load: 'Load',
-    removeImage: 'Remove image',
-    screenPreview: 'Screen {n} preview',
-    imageLoaded: 'Image loaded',
-    imageLoadError: 'Image load error: {error}',
-    enterImageUrl: 'Enter image URL',
-    textAdded: 'Text added',
-    textSent: 'Text sent to device',
-    textSendError: 'Error sending text: {error}',
-  },
-  screenManager: {
-    title: 'Times Gate Screen Configuration',
-    save: 'Save',
-    sendToDevice: 'Send to Device',
-    screenN: 'Screen {n}',
-    configSaved: 'Configuration saved',
-    configSaveError: 'Failed to save configuration',
-    sendingAll: 'Sending all configurations to device...',
-    allSent: 'All configurations sent to device',
-    sendError: 'Failed to send configurations to device',
-  },
-  textElement: {
-    editText: 'Text Editor',
-    text: 'Text',
-    textContent: 'Text content',
-    fontType: 'Font Type',
-    textWidth: 'Text Width',
-    positionX: 'Position (X)',
-    positionY: 'Position (Y)',
-    addText: 'Add text',
-    sendToDevice: 'Send to device',
-  },
-  themeToggle: {
-    switchToLight: 'Switch to light theme',
-    switchToDark: 'Switch to dark theme',
-  },
-};
+export default {
+  app: {
+    title: 'Divoom Device Monitor',
+    routeNotFound: 'Route not found: {path}',
+    alertTriggered: '{name}: {metric} is {value} (threshold {threshold})',
+    alertCleared: '{name}: {metric} is back to normal ({value})',
+  },
+  language: {
+    ru: 'Русский',
+    en: 'English',
+  },
+  header: {
+    appSettings: 'App Settings',
+  },
+  appSettings: {
+    title: 'App Settings',
+    appearance: 'Appearance',
+    appTheme: 'App Theme',
+    dark: 'Dark',
+    light: 'Light',
+    switchToLight: 'Switch to light theme',
+    switchToDark: 'Switch to dark theme',
+    behavior: 'Behavior',
+    autostart: 'Launch at Windows startup',
+    autostartDescription: 'The app will start automatically when you log in',
+    closeToTray: 'Minimize to tray on close',
+    closeToTrayDescription: 'The app will minimize to the system tray instead of closing completely',
+    language: 'Language',
+    languageLabel: 'App language',
+    updates: 'Updates',
+    currentVersion: 'Current version',
+    checkForUpdates: 'Check for updates',
+    checking: 'Checking...',
+    updateAvailable: 'Version {version} available',
+    noUpdates: 'You are on the latest version',
+    downloading: 'Downloading update...',
+    downloadProgress: 'Downloaded {downloaded} of {total}',
+    installAndRestart: 'Install and restart',
+    updateError: 'Failed to check for updates',
+    releaseNotes: 'Release notes',
+  },
+  deviceList: {
+    scanning: 'Scanning...',
+    scanDevices: 'Scan Devices',
+    scanFailed: 'Failed to scan devices',
+    noDevices: "No devices found. Click 'Scan Devices' to search.",
+  },
+  deviceCard: {
+    connected: 'Connected',
+    disconnected: 'Disconnected',
+    deviceType: 'Device Type',
+    ipAddress: 'IP Address',
+    macAddress: 'MAC Address',
+    id: 'ID',
+    signalStrength: 'Signal Strength',
+  },
+  settingsLayout: {
+    settings: 'Settings',
+    commonSettings: 'General Settings',
+    screenSettings: 'Screen Settings',
+    systemStatus: 'System Status',
+    deviceSettings: 'Device Settings',
+  },
+  deviceSettings: {
+    loading: 'Loading...',
+  },
+  displaySettings: {
+    timesGateOnly: 'Screen settings are only available for Times Gate devices',
+  },
+  commonSettings: {
+    currentSettings: 'Current Settings',
+    refreshSettings: 'Refresh settings',
+    rebootDevice: 'Reboot device',
+    basicSettings: 'Basic Settings',
+    powerToggle: 'Power on/off',
+    brightness: 'Brightness',
+    mirror: 'Mirror',
+    temperatureFormat: 'Temperature Format',
+    celsius: 'Celsius',
+    fahrenheit: 'Fahrenheit',
+    timeFormat24: '24-hour format',
+    noSettings: "Settings not loaded. Click 'Refresh' to load.",
+    invalidIp: 'Invalid IP address format',
+    loadError: 'Failed to load device settings',
+    deviceState: 'Device State',
+    copyState: 'Copy',
+    stateCopied: 'Device state copied to clipboard',
+    rawState: 'Other reported values',
+    state: {
+      firmware_version: 'Firmware version',
+      device_local_time: 'Device time',
+      current_channel: 'Current channel',
+      current_clock_id: 'Clock face ID',
+      custom_page_index: 'Custom page',
+      power_on_channel_id: 'Channel after power on',
+      gyrate_angle: 'Rotation angle',
+      rotation_flag: 'Channel rotation',
+      clock_time: 'Clock rotation time, s',
+      gallery_time: 'Gallery rotation time, s',
+      single_gallery_time: 'Time per picture, s',
+      date_format: 'Date format',
+    },
+  },
+  systemMetrics: {
+    title: 'System Status',
+    subtitle: 'Real-time load and temperature metrics',
+    updated: 'Updated: {time}',
+    refresh: 'Refresh',
+    cpu: 'CPU',
+    gpu: 'GPU',
+    ram: 'RAM',
+    load: 'Load',
+    temperature: 'Temperature',
+    gpuHint: 'GPU data is unavailable. Make sure the sidecar is running with admin privileges.',
+    usage: 'Usage',
+    of: 'of',
+    disks: 'Disks',
+    disksUnavailable: 'Disk data is unavailable.',
+    disk: 'Disk',
+    sendToDevice: 'Send to Device',
+    screen: 'Screen',
+    screenN: 'Screen {n}',
+    screenPcMonitor: 'Screen {n} (PC Monitor)',
+    screenClock: 'Screen {n} (Clock: {clockId})',
+    pcMonitorMode: 'PC Monitor Mode',
+    alreadyActivated: 'Already activated',
+    activate: 'Activate',
+    autoSendMetrics: 'Auto-send metrics',
+    on: 'On',
+    off: 'Off',
+    metricsSending: 'Metrics are being sent in the background every 2 sec.',
+    sentData: 'Data being sent:',
+    pcMonitorActivated: 'PC Monitor activated on screen {n}',
+    activationError: 'Activation error: {error}',
+    metricsError: 'Failed to get system metrics',
+    lcdError: 'Failed to get screen information',
+  },
+  screenEditor: {
+    image: 'Image',
+    loadFromComputer: 'Load from computer',
+    imageUrl: 'Image URL',
+    load: 'Load',
+    removeImage: 'Remove image',
+    screenPreview: 'Screen {n} preview',
+    imageLoaded: 'Image loaded',
+    imageLoadError: 'Image load error: {error}',
+    enterImageUrl: 'Enter image URL',
+    textAdded: 'Text added',
+    textSent: 'Text sent to device',
+    textSendError: 'Error sending text: {error}',
+  },
+  screenManager: {
+    title: 'Times Gate Screen Configuration',
+    save: 'Save',
+    sendToDevice: 'Send to Device',
+    screenN: 'Screen {n}',
+    configSaved: 'Configuration saved',
+    configSaveError: 'Failed to save configuration',
+    sendingAll: 'Sending all configurations to device...',
+    allSent: 'All configurations sent to device',
+    sendError: 'Failed to send configurations to device',
+  },
+  textElement: {
+    editText: 'Text Editor',
+    text: 'Text',
+    textContent: 'Text content',
+    fontType: 'Font Type',
+    textWidth: 'Text Width',
+    positionX: 'Position (X)',
+    positionY: 'Position (Y)',
+    addText: 'Add text',
+    sendToDevice: 'Send to device',
+  },
+  themeToggle: {
+    switchToLight: 'Switch to light theme',
+    switchToDark: 'Switch to dark theme',
+  },
+};
@@ -1,158 +1,178 @@
-export default {
-  app: {
-    title: 'Divoom Device Monitor',
-    routeNotFound: 'Маршрут не найден: {path}',
-  },
-  language: {
-    ru: 'Русский',
-    en: 'English',
-  },
-  header: {
-    appSettings: 'Настройки приложения',
-  },
-  appSettings: {
-    title: 'Настройки приложения',
-    appearance: 'Внешний вид',
-    appTheme: 'Тема приложения',
-    dark: 'Тёмная',
-    light: 'Светлая',
-    switchToLight: 'Переключить на светлую тему',
-    switchToDark: 'Переключить на тёмную тему',
-    behavior: 'Поведение',
-    autostart: 'Автозапуск при старте Windows',
-    autostartDescription: 'Приложение запустится автоматически при входе в систему',
-    closeToTray: 'Сворачивать в трей при закрытии',
-    closeToTrayDescription: 'Приложение свернётся в системный трей вместо полного закрытия',
-    language: 'Язык',
-    languageLabel: 'Язык приложения',
-    updates: 'Обновления',
-    currentVersion: 'Текущая версия',
-    checkForUpdates: 'Проверить обновления',
-    checking: 'Проверка...',
-    updateAvailable: 'Доступна версия {version}',
-    noUpdates: 'Установлена последняя версия',
-    downloading: 'Загрузка обновления...',
-    downloadProgress: 'Загружено {downloaded} из {total}',
-    installAndRestart: 'Установить и перезапустить',
-    updateError: 'Ошибка проверки обновлений',
-    releaseNotes: 'Описание обновления',
-  },
-  deviceList: {
-    scanning: 'Сканирование...',
-    scanDevices: 'Сканировать устройства',
-    scanFailed: 'Не удалось просканировать устройства',
-    noDevices: "Устройства не найдены. Нажмите 'Сканировать устройства' для поиска.",
-  },
-  deviceCard: {
-    connected: 'Подключено',
-    disconnected: 'Не подключено',
-    deviceType: 'Тип устройства',
-    ipAddress: 'IP адрес',
-    macAddress: 'MAC адрес',
-    id: 'ID',
-    signalStrength: 'Уровень сигнала',
-  },
-  settingsLayout: {
-    settings: 'Настройки',
-    commonSettings: 'Общие настройки',
-    screenSettings: 'Настройки экранов',
-    systemStatus: 'Состояние системы',
-    deviceSettings: 'Настройки устройства',
-  },
-  deviceSettings: {
-    loading: 'Загрузка...',
-  },
-  displaySettings: {
-    timesGateOnly: 'Настройки экранов доступны только для устройств Times Gate',
-  },
-  commonSettings: {
-    currentSettings: 'Текущие настройки',
-    refreshSettings: 'Обновить настройки',
-    rebootDevice: 'Перезагрузить устройство',
-    basicSettings: 'Основные настройки',
-    powerToggle: 'Включить\\выключить',
-    brightness: 'Яркость',
-    mirror: 'Отзеркалить',
-    temperatureFormat: 'Формат температуры',
-    celsius: 'Цельсий',
-    fahrenheit: 'Фаренгейт',
-    timeFormat24: '24-часовой формат',
-    noSettings: "Настройки не загружены. Нажмите 'Обновить' для загрузки.",
-    invalidIp: 'Неверный формат IP адреса',
-    loadError: 'Ошибка загрузки настроек устройства',
-  },
-  systemMetrics: {
-    title: 'Состояние системы',
-    subtitle: 'Актуальные показатели загрузки и температуры',
-    updated: 'Обновлено: {time}',
-    refresh: 'Обновить',
-    cpu: 'CPU',
-    gpu: 'GPU',
-    ram: 'RAM',
-    load: 'Загрузка',
-    temperature: 'Температура',
-    gpuHint: 'Данные GPU недоступны. Убедитесь, что sidecar запущен с правами администратора.',
-    usage: 'Использование',
-    of: 'из',
-    disks: 'Диски',
-    disksUnavailable: 'Данные о дисках недоступны.',
-    disk: 'Диск',
-    sendToDevice: 'Отправка на устройство',
-    screen: 'Экран',
-    screenN: 'Экран {n}',
-    screenPcMonitor: 'Экран {n} (PC Monitor)',
-    screenClock: 'Экран {n} (Clock: {clockId})',
-    pcMonitorMode: 'Режим PC Monitor',
-    alreadyActivated: 'Уже активирован',
-    activate: 'Активировать',
-    autoSendMetrics: 'Автоотправка метрик',
-    on: 'Вкл',
-    off: 'Выкл',
-    metricsSending: 'Метрики отправляются в фоновом режиме каждые 2 сек.',
-    sentData: 'Отправляемые данные:',
-    pcMonitorActivated: 'PC Monitor активирован на экране {n}',
-    activationError: 'Ошибка активации: {error}',
-    metricsError: 'Не удалось получить метрики системы',
-    lcdError: 'Не удалось получить информацию об экранах',
-  },
-  screenEditor: {
-    image: 'Изображение',
-    loadFromComputer: 'Загрузить с компьютера',
-    imageUrl: 'URL изображения',
-    load: 'Загрузить',
-    removeImage: 'Удалить изображение',
-    screenPreview: 'Предпросмотр экрана {n}',
-    imageLoaded: 'Изображение загружено',
-    imageLoadError: 'Ошибка загрузки изображения: {error}',
-    enterImageUrl: 'Введите URL изображения',
-    textAdded: 'Текст добавлен',
-    textSent: 'Текст отправлен на устройство',
-    textSendError: 'Ошибка отправки текста: {error}',
-  },
-  screenManager: {
-    title: 'Настройка экранов Times Gate',
-    save: 'Сохранить',
-    sendToDevice: 'Отправить на устройство',
-    screenN: 'Экран {n}',
-    configSaved: 'Конфигурация сохранена',
-    configSaveError: 'Ошибка сохранения конфигурации',
-    sendingAll: 'Отправка всех конфигураций на устройство...',
-    allSent: 'Все конфигурации отправлены на устройство',
-    sendError: 'Ошибка отправки конфигураций на устройство',
-  },
-  textElement: {
-    editText: 'Редактирование текста',
-    text: 'Текст',
-    textContent: 'Содержимое текста',
-    fontType: 'Тип шрифта',
-    textWidth: 'Ширина текста',
-    positionX: 'Позиция (X)',
-    positionY: 'Позиция (Y)',
-    addText: 'Добавить текст',
-    sendToDevice: 'Отправить на устройство',
-  },
-  themeToggle: {
-    switchToLight: 'Переключить на светлую тему',
-    switchToDark: 'Переключить на тёмную тему',
-  },
-};
+export default {
+  app: {
+    title: 'Divoom Device Monitor',
+    routeNotFound: 'Маршрут не найден: {path}',
+    alertTriggered: '{name}: {metric} = {value} (порог {threshold})',
+    alertCleared: '{name}: {metric} вернулся в норму ({value})',
+  },
+  language: {
+    ru: 'Русский',
+    en: 'English',
+  },
+  header: {
+    appSettings: 'Настройки приложения',
+  },
+  appSettings: {
+    title: 'Настройки приложения',
+    appearance: 'Внешний вид',
+    appTheme: 'Тема приложения',
+    dark: 'Тёмная',
+    light: 'Светлая',
+    switchToLight: 'Переключить на светлую тему',
+    switchToDark: 'Переключить на тёмную тему',
+    behavior: 'Поведение',
+    autostart: 'Автозапуск при старте Windows',
+    autostartDescription: 'Приложение запустится автоматически при входе в систему',
+    closeToTray: 'Сворачивать в трей при закрытии',
+    closeToTrayDescription: 'Приложение свернётся в системный трей вместо полного закрытия',
+    language: 'Язык',
+    languageLabel: 'Язык приложения',
+    updates: 'Обновления',
+    currentVersion: 'Текущая версия',
+    checkForUpdates: 'Проверить обновления',
+    checking: 'Проверка...',
+    updateAvailable: 'Доступна версия {version}',
+    noUpdates: 'Установлена последняя версия',
+    downloading: 'Загрузка обновления...',
+    downloadProgress: 'Загружено {downloaded} из {total}',
+    installAndRestart: 'Установить и перезапустить',
+    updateError: 'Ошибка проверки обновлений',
+    releaseNotes: 'Описание обновления',
+  },
+  deviceList: {
+    scanning: 'Сканирование...',
+    scanDevices: 'Сканировать устройства',
+    scanFailed: 'Не удалось просканировать устройства',
+    noDevices: "Устройства не найдены. Нажмите 'Сканировать устройства' для поиска.",
+  },
+  deviceCard: {
+    connected: 'Подключено',
+    disconnected: 'Не подключено',
+    deviceType: 'Тип устройства',
+    ipAddress: 'IP адрес',
+    macAddress: 'MAC адрес',
+    id: 'ID',
+    signalStrength: 'Уровень сигнала',
+  },
+  settingsLayout: {
+    settings: 'Настройки',
+    commonSettings: 'Общие настройки',
+    screenSettings: 'Настройки экранов',
+    systemStatus: 'Состояние системы',
+    deviceSettings: 'Настройки устройства',
+  },
+  deviceSettings: {
+    loading: 'Загрузка...',
+  },
+  displaySettings: {
+    timesGateOnly: 'Настройки экранов доступны только для устройств Times Gate',
+  },
+  commonSettings: {
+    currentSettings: 'Текущие настройки',
+    refreshSettings: 'Обновить настройки',
+    rebootDevice: 'Перезагрузить устройство',
+    basicSettings: 'Основные настройки',
+    powerToggle: 'Включить\\выключить',
+    brightness: 'Яркость',
+    mirror: 'Отзеркалить',
+    temperatureFormat: 'Формат температуры',
+    celsius: 'Цельсий',
+    fahrenheit: 'Фаренгейт',
+    timeFormat24: '24-часовой формат',
+    noSettings: "Настройки не загружены. Нажмите 'Обновить' для загрузки.",
+    invalidIp: 'Неверный формат IP адреса',
+    loadError: 'Ошибка загрузки настроек устройства',
+    deviceState: 'Состояние устройства',
+    copyState: 'Копировать',
+    stateCopied: 'Состояние устройства скопировано в буфер обмена',
+    rawState: 'Прочие значения',
+    state: {
+      firmware_version: 'Версия прошивки',
+      device_local_time: 'Время устройства',
+      current_channel: 'Текущий канал',
+      current_clock_id: 'ID циферблата',
+      custom_page_index: 'Пользовательская страница',
+      power_on_channel_id: 'Канал после включения',
+      gyrate_angle: 'Угол поворота',
+      rotation_flag: 'Ротация каналов',
+      clock_time: 'Время показа часов, с',
+      gallery_time: 'Время показа галереи, с',
+      single_gallery_time: 'Время на картинку, с',
+      date_format: 'Формат даты',
+    },
+  },
+  systemMetrics: {
+    title: 'Состояние системы',
+    subtitle: 'Актуальные показатели загрузки и температуры',
+    updated: 'Обновлено: {time}',
+    refresh: 'Обновить',
+    cpu: 'CPU',
+    gpu: 'GPU',
+    ram: 'RAM',
+    load: 'Загрузка',
+    temperature: 'Температура',
+    gpuHint: 'Данные GPU недоступны. Убедитесь, что sidecar запущен с правами администратора.',
+    usage: 'Использование',
+    of: 'из',
+    disks: 'Диски',
+    disksUnavailable: 'Данные о дисках недоступны.',
+    disk: 'Диск',
+    sendToDevice: 'Отправка на устройство',
+    screen: 'Экран',
+    screenN: 'Экран {n}',
+    screenPcMonitor: 'Экран {n} (PC Monitor)',
+    screenClock: 'Экран {n} (Clock: {clockId})',
+    pcMonitorMode: 'Режим PC Monitor',
+    alreadyActivated: 'Уже активирован',
+    activate: 'Активировать',
+    autoSendMetrics: 'Автоотправка метрик',
+    on: 'Вкл',
+    off: 'Выкл',
+    metricsSending: 'Метрики отправляются в фоновом режиме каждые 2 сек.',
+    sentData: 'Отправляемые данные:',
+    pcMonitorActivated: 'PC Monitor активирован на экране {n}',
+    activationError: 'Ошибка активации: {error}',
+    metricsError: 'Не удалось получить метрики системы',
+    lcdError: 'Не удалось получить информацию об экранах',
+  },
+  screenEditor: {
+    image: 'Изображение',
+    loadFromComputer: 'Загрузить с компьютера',
+    imageUrl: 'URL изображения',
+    load: 'Загрузить',
+    removeImage: 'Удалить изображение',
+    screenPreview: 'Предпросмотр экрана {n}',
+    imageLoaded: 'Изображение загружено',
+    imageLoadError: 'Ошибка загрузки изображения: {error}',
+    enterImageUrl: 'Введите URL изображения',
+    textAdded: 'Текст добавлен',
+    textSent: 'Текст отправлен на устройство',
+    textSendError: 'Ошибка отправки текста: {error}',
+  },
+  screenManager: {
+    title: 'Настройка экранов Times Gate',
+    save: 'Сохранить',
+    sendToDevice: 'Отправить на устройство',
+    screenN: 'Экран {n}',
+    configSaved: 'Конфигурация сохранена',
+    configSaveError: 'Ошибка сохранения конфигурации',
+    sendingAll: 'Отправка всех конфигураций на устройство...',
+    allSent: 'Все конфигурации отправлены на устройство',
+    sendError: 'Ошибка отправки конфигураций на устройство',
+  },
+  textElement: {
+    editText: 'Редактирование текста',
+    text: 'Текст',
+    textContent: 'Содержимое текста',
+    fontType: 'Тип шрифта',
+    textWidth: 'Ширина текста',
+    positionX: 'Позиция (X)',
+    positionY: 'Позиция (Y)',
+    addText: 'Добавить текст',
+    sendToDevice: 'Отправить на устройство',
+  },
+  themeToggle: {
+    switchToLight: 'Переключить на светлую тему',
+    switchToDark: 'Переключить на тёмную тему',
+  },
+};
@@ -1,19 +1,19 @@
-import { createApp } from 'vue';
-import ElementPlus from 'element-plus';
-import 'element-plus/dist/index.css';
-import 'element-plus/theme-chalk/dark/css-vars.css';
-import App from './App.vue';
-import router from './router';
-import i18n from './i18n';
-import { useTheme } from './composables/useTheme';
-
-const app = createApp(App);
-
-app.use(ElementPlus);
-app.use(router);
-app.use(i18n);
-
-const { initTheme } = useTheme();
-initTheme();
-
-app.mount('#app');
+import { createApp } from 'vue';
+import ElementPlus from 'element-plus';
+import 'element-plus/dist/index.css';
+import 'element-plus/theme-chalk/dark/css-vars.css';
+import App from './App.vue';
+import router from './router';
+import i18n from './i18n';
+import { useTheme } from './composables/useTheme';
+
+const app = createApp(App);
+
+app.use(ElementPlus);
+app.use(router);
+app.use(i18n);
+
+const { initTheme } = useTheme();
+initTheme();
+
+app.mount('#app');
@@ -18,9 +18,312 @@ export interface NetworkSettings {
 export interface DeviceSettings {
   brightness?: number;
   rotation_flag: number;
+  clock_time: number | null;
+  gallery_time: number | null;
+  single_gallery_time: number | null;
+  power_on_channel_id: number | null;
+  gallery_show_time_flag: number | null;
+  current_clock_id: number | null;
+  current_channel: number | null;
+  custom_page_index: number | null;
+  gyrate_angle: number | null;
   date_format: string;
   time24_flag: number;
   temperature_mode: number;
   mirror_flag: number;
   light_switch: number;
+  firmware_version: string | null;
+  device_utc_time: number | null;
+  device_local_time: string | null;
+  raw: Record<string, unknown>;
+}
+
+export interface TimeSyncSettings {
+  enabled: boolean;
+  interval_hours: number;
+  /** IP addresses of the devices to keep in sync. */
+  devices: string[];
+}
+
+export interface WeatherInfo {
+  weather: string | null;
+  cur_temp: number | null;
+  min_temp: number | null;
+  max_temp: number | null;
+  pressure: number | null;
+  humidity: number | null;
+  visibility: number | null;
+  wind_speed: number | null;
+}
+
+export type Channel = 'faces' | 'cloud' | 'visualizer' | 'custom' | 'black_screen';
+
+export type CloudChannel = 'recommended' | 'favourites' | 'artists';
+
+export interface ChannelSelection {
+  /** `null` when the firmware reports an unknown channel index. */
+  channel: Channel | null;
+  clock_id: number | null;
+  custom_page_index: number | null;
+}
+
+export interface ClockFace {
+  clock_id: number;
+  name: string;
+}
+
+export interface ClockFacePage {
+  total: number;
+  faces: ClockFace[];
+}
+
+/** LCD the tray menu flips between a clock face and PC Monitor. */
+export interface TrayChannelSettings {
+  ip_address: string;
+  lcd_index: number;
+  /** Face restored by the tray; defaults to the one shown before PC Monitor. */
+  clock_id: number | null;
+}
+
+export type BuzzerPattern = 'short_beep' | 'triple_beep' | 'alarm';
+
+/** One buzzer run; all times in milliseconds. */
+export interface BuzzerTiming {
+  active_ms: number;
+  off_ms: number;
+  total_ms: number;
+}
+
+export type Tool = 'timer' | 'stopwatch' | 'scoreboard' | 'noise_meter';
+
+export type StopwatchAction = 'start' | 'stop' | 'reset';
+
+export type Team = 'red' | 'blue';
+
+export interface TimerState {
+  running: boolean;
+  duration_secs: number;
+  /** Unix time the countdown ends, while running. */
+  ends_at: number | null;
+}
+
+export interface StopwatchState {
+  running: boolean;
+  /** Seconds counted before the current run. */
+  elapsed_secs: number;
+  /** Unix time of the current run's start. */
+  started_at: number | null;
+}
+
+export interface ToolState {
+  /** Tool last shown on the device. */
+  active: Tool | null;
+  timer: TimerState;
+  stopwatch: StopwatchState;
+  scoreboard: { red: number; blue: number };
+  noise_meter: boolean;
+}
+
+export interface WeatherLocation {
+  longitude: number;
+  latitude: number;
+}
+
+export interface LocalTemperaturePush {
+  enabled: boolean;
+  /** Metric name, usually a custom provider slot. */
+  metric: string;
+  ip_address: string;
+  lcd_index: number;
+  text_id: number;
+  x: number;
+  y: number;
+  color: string | null;
+  interval_secs: number;
+}
+
+export interface WeatherSettings {
+  location: WeatherLocation | null;
+  /** IP addresses that receive `location` when the settings are saved. */
+  devices: string[];
+  local_temperature: LocalTemperaturePush[];
+}
+
+export type ScheduleTime =
+  | { kind: 'at'; hour: number; minute: number }
+  | { kind: 'sunrise'; offset_minutes: number }
+  | { kind: 'sunset'; offset_minutes: number };
+
+export interface ScheduleEntry {
+  enabled: boolean;
+  time: ScheduleTime;
+  /** 0 - Sunday ... 6 - Saturday, as in cron; empty means every day. */
+  weekdays: number[];
+  brightness: number | null;
+  screen_on: boolean | null;
+}
+
+export interface BrightnessSchedule {
+  enabled: boolean;
+  /** IP addresses of the devices the schedule controls. */
+  devices: string[];
+  entries: ScheduleEntry[];
+}
+
+export interface SunTimes {
+  /** Unix time; `null` during polar day or night. */
+  sunrise: number | null;
+  sunset: number | null;
+}
+
+/** Firmware font; the backend also accepts its number (0-7). */
+export type Font =
+  | 'arial'
+  | 'times_new_roman'
+  | 'verdana'
+  | 'courier_new'
+  | 'georgia'
+  | 'garamond'
+  | 'comic_sans_ms'
+  | 'impact';
+
+/** Text placement; the backend also accepts its number (0-4). */
+export type TextAlignment = 'scroll' | 'normal' | 'middle' | 'right' | 'left';
+
+export type ScrollDirection = 'left' | 'right';
+
+export type ScrollSpeed = 'slow' | 'normal' | 'fast';
+
+/**
+ * Text element as the backend sends it (`TextConfig` in Rust). The backend
+ * returns names and `#RRGGBB` colors; `r,g,b` and CSS color names are accepted too.
+ */
+export interface TextConfig {
+  id: number;
+  content: string;
+  x: number;
+  y: number;
+  font: Font | number | null;
+  color: string | null;
+  alignment: TextAlignment | number | null;
+  text_width: number | null;
+  direction?: ScrollDirection | null;
+  speed?: ScrollSpeed | null;
+}
+
+/** What a text layout element shows; all but `text` are rendered by the device. */
+export type DisplayItemKind =
+  | 'text'
+  | 'time'
+  | 'time_with_seconds'
+  | 'year'
+  | 'day'
+  | 'month'
+  | 'weekday'
+  | 'temperature'
+  | 'max_temperature'
+  | 'min_temperature'
+  | 'weather'
+  | 'noise';
+
+/** Element of a text layout; `content` is only shown for `text`. */
+export type DisplayItem = TextConfig & { kind: DisplayItemKind };
+
+export type JobAction =
+  | { kind: 'set_brightness'; ip_address: string; brightness: number }
+  | { kind: 'set_screen'; ip_address: string; on: boolean }
+  | { kind: 'upload_image'; ip_address: string; lcd_index: number; source: string }
+  | {
+      kind: 'show_text';
+      ip_address: string;
+      lcd_index: number;
+      text_config: TextConfig;
+    }
+  | { kind: 'activate_pc_monitor'; ip_address: string; lcd_index: number }
+  | { kind: 'select_clock'; ip_address: string; lcd_index: number; clock_id: number }
+  | { kind: 'push_system_metrics'; ip_address: string; lcd_index: number }
+  | { kind: 'play_buzzer'; ip_address: string; pattern: BuzzerPattern }
+  | { kind: 'reboot'; ip_address: string };
+
+export interface AutomationJob {
+  id: string;
+  name: string;
+  /** Five-field cron expression in local time, e.g. `0 9 * * 1-5`. */
+  schedule: string;
+  enabled: boolean;
+  actions: JobAction[];
+}
+
+export interface JobRun {
+  job_id: string;
+  /** Unix time the run started. */
+  started_at: number;
+  duration_ms: number;
+  /** Errors of the failed actions; empty when the run succeeded. */
+  errors: string[];
+}
+
+export type Transition = 'none' | 'fade' | 'slide';
+
+export type PlaylistItem =
+  | { kind: 'image'; source: string }
+  | { kind: 'text'; background: string | null; texts: TextConfig[] };
+
+export type PlaylistEntry = PlaylistItem & {
+  dwell_secs: number;
+  transition: Transition;
+};
+
+export interface Playlist {
+  id: string;
+  name: string;
+  ip_address: string;
+  lcd_index: number;
+  enabled: boolean;
+  paused: boolean;
+  entries: PlaylistEntry[];
+}
+
+export interface PlaylistStatus {
+  id: string;
+  /** Entry on screen; `null` before the first one was shown. */
+  position: number | null;
+  paused: boolean;
+  /** Seconds until the next entry, while playing. */
+  next_in_secs: number | null;
+  last_error: string | null;
+}
+
+export interface SceneScreen {
+  lcd_index: number;
+  /** Clock face selected before the image and texts are drawn. */
+  clock_id: number | null;
+  /** http(s) URL or local file path. */
+  image: string | null;
+  texts: TextConfig[];
+}
+
+export interface Scene {
+  name: string;
+  /** Device type the scene was captured on; other types are refused. */
+  device_type: string | null;
+  brightness: number | null;
+  screens: SceneScreen[];
+  /** LCD switched to PC Monitor, if any. */
+  pc_monitor_lcd: number | null;
+}
+
+/** Named set of devices driven together. */
+export interface DeviceGroup {
+  id: string;
+  name: string;
+  /** IP addresses of the member devices. */
+  devices: string[];
+}
+
+/** Outcome of a group command on one device. */
+export interface DeviceResult {
+  ip_address: string;
+  /** `null` when the command succeeded. */
+  error: string | null;
 }
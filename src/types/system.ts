@@ -1,3 +1,5 @@
+import type { BuzzerPattern } from './device';
+
 export interface DiskUsage {
   name: string;
   mount_point: string;
@@ -15,6 +17,32 @@ export interface SystemMetrics {
   memory_total: number;
   memory_used: number;
   disks: DiskUsage[];
+  custom: Record<string, number>;
+}
+
+export interface SensorInfo {
+  id: string;
+  label: string;
+  kind: string;
+  unit: string;
+  value: number | null;
+  provider: string;
+}
+
+export type SensorMapping = Record<string, string>;
+
+export type MetricProviderSource =
+  | { kind: 'command'; command: string }
+  | { kind: 'file'; path: string }
+  | { kind: 'http_json'; url: string; pointer: string };
+
+export interface MetricProviderConfig {
+  id: string;
+  metric: string;
+  source: MetricProviderSource;
+  priority: number;
+  enabled: boolean;
+  timeout_ms: number;
 }
 
 export interface LcdInfo {
@@ -30,3 +58,80 @@ export interface LcdInfoResponse {
   device_id: number;
   independence_list: LcdIndependenceInfo[];
 }
+
+export type MetricSmoothing =
+  | { kind: 'ema'; alpha: number }
+  | { kind: 'median'; window: number };
+
+export type UnitConversion = 'celsius_to_fahrenheit' | 'bytes_to_gb' | 'bytes_to_gib';
+
+export interface MetricPipeline {
+  smoothing?: MetricSmoothing | null;
+  conversion?: UnitConversion | null;
+  min?: number | null;
+  max?: number | null;
+  precision?: number | null;
+}
+
+// target ('ui', 'device' or a device IP) -> metric -> pipeline
+export type MetricPipelines = Record<string, Record<string, MetricPipeline>>;
+
+export interface ProcessedValue {
+  value: number;
+  unit: string;
+  text: string;
+}
+
+export type ProcessedMetrics = Record<string, ProcessedValue>;
+
+export type AlertComparison = 'above' | 'below';
+
+export type AlertAction =
+  | { kind: 'flash'; ip_address: string; lcd_index: number; color: string; times: number }
+  | {
+      kind: 'show_text';
+      ip_address: string;
+      lcd_index: number;
+      text: string;
+      color?: string | null;
+    }
+  | { kind: 'show_image'; ip_address: string; lcd_index: number; source: string }
+  | { kind: 'set_brightness'; ip_address: string; brightness: number }
+  | { kind: 'play_buzzer'; ip_address: string; pattern: BuzzerPattern }
+  | { kind: 'notify' };
+
+export interface AlertRule {
+  id: string;
+  name: string;
+  metric: string;
+  comparison: AlertComparison;
+  threshold: number;
+  duration_secs: number;
+  hysteresis: number;
+  cooldown_secs: number;
+  enabled: boolean;
+  actions: AlertAction[];
+  clear_actions: AlertAction[];
+}
+
+export interface AlertEvent {
+  rule_id: string;
+  name: string;
+  metric: string;
+  value: number;
+  threshold: number;
+  state: 'triggered' | 'cleared';
+}
+
+export interface PrometheusSettings {
+  enabled: boolean;
+  bind_address: string;
+  port: number;
+}
+
+export interface RestApiSettings {
+  enabled: boolean;
+  bind_address: string;
+  port: number;
+  token: string;
+}